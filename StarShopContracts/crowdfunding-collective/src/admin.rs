@@ -0,0 +1,261 @@
+use crate::funding::{get_expected_balance, BPS_DENOMINATOR};
+use crate::product::{default_funding_model, DEFAULT_MAX_MILESTONES, DEFAULT_MAX_REWARD_TIERS};
+use crate::types::*;
+use soroban_sdk::{token, Address, BytesN, Env, Symbol, Vec};
+
+// Any admin can grant admin rights to another address
+pub fn add_admin(env: Env, caller: Address, new_admin: Address) {
+    require_admin(&env, &caller);
+
+    let mut admins = get_admins(env.clone());
+    if admins.contains(&new_admin) {
+        panic!("Address is already an admin");
+    }
+    admins.push_back(new_admin.clone());
+    env.storage().instance().set(&DataKey::Admins, &admins);
+
+    env.events()
+        .publish((Symbol::new(&env, "AdminAdded"), caller), new_admin);
+}
+
+// Any admin can revoke another admin (or themselves), but the set can never become empty
+pub fn remove_admin(env: Env, caller: Address, target: Address) {
+    require_admin(&env, &caller);
+
+    let mut admins = get_admins(env.clone());
+    let index = admins
+        .first_index_of(&target)
+        .unwrap_or_else(|| panic!("Address is not an admin"));
+    if admins.len() == 1 {
+        panic!("Cannot remove last admin");
+    }
+    admins.remove(index);
+    env.storage().instance().set(&DataKey::Admins, &admins);
+
+    env.events()
+        .publish((Symbol::new(&env, "AdminRemoved"), caller), target);
+}
+
+pub fn get_admins(env: Env) -> Vec<Address> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Admins)
+        .unwrap_or_else(|| panic!("Contract not initialized"))
+}
+
+pub fn upgrade(env: Env, admin: Address, new_wasm_hash: BytesN<32>) {
+    require_admin(&env, &admin);
+
+    env.deployer()
+        .update_current_contract_wasm(new_wasm_hash.clone());
+
+    env.events()
+        .publish((Symbol::new(&env, "Upgraded"), admin), new_wasm_hash);
+}
+
+// Code version reported by get_version; bump it with every release that ships through upgrade
+pub const VERSION: u32 = 1;
+
+pub fn get_version(_env: Env) -> u32 {
+    VERSION
+}
+
+pub fn get_config(env: Env) -> ContractConfig {
+    let storage = env.storage().instance();
+    ContractConfig {
+        token: storage.get(&DataKey::Token),
+        native_token: storage.get(&DataKey::NativeToken),
+        withdraw_fee_bps: storage.get(&DataKey::WithdrawFeeBps).unwrap_or(0),
+        min_campaign_duration: storage.get(&DataKey::MinCampaignDuration).unwrap_or(0),
+        max_funding_goal: storage.get(&DataKey::MaxFundingGoal).unwrap_or(0),
+        max_milestones: storage
+            .get(&DataKey::MaxMilestones)
+            .unwrap_or(DEFAULT_MAX_MILESTONES),
+        max_reward_tiers: storage
+            .get(&DataKey::MaxRewardTiers)
+            .unwrap_or(DEFAULT_MAX_REWARD_TIERS),
+        refund_grace_period: storage.get(&DataKey::RefundGracePeriod).unwrap_or(0),
+        failure_bonus_bps: storage.get(&DataKey::FailureBonusBps).unwrap_or(0),
+        require_verified_creators: storage
+            .get(&DataKey::RequireVerifiedCreators)
+            .unwrap_or(false),
+        default_funding_model: default_funding_model(&env),
+    }
+}
+
+// Token that products created from now on will accept
+pub fn set_token(env: Env, admin: Address, token: Address) {
+    require_admin(&env, &admin);
+    env.storage().instance().set(&DataKey::Token, &token);
+}
+
+// SAC address of the network's native asset, used by create_native_product
+pub fn set_native_token(env: Env, admin: Address, token: Address) {
+    require_admin(&env, &admin);
+    env.storage().instance().set(&DataKey::NativeToken, &token);
+}
+
+pub fn set_withdraw_fee(env: Env, admin: Address, fee_bps: u32) {
+    require_admin(&env, &admin);
+    if fee_bps as u64 > BPS_DENOMINATOR {
+        panic!("Fee cannot exceed 100%");
+    }
+    env.storage()
+        .instance()
+        .set(&DataKey::WithdrawFeeBps, &fee_bps);
+}
+
+// Campaigns must run at least this many seconds from creation to deadline
+pub fn set_min_campaign_duration(env: Env, admin: Address, seconds: u64) {
+    require_admin(&env, &admin);
+    env.storage()
+        .instance()
+        .set(&DataKey::MinCampaignDuration, &seconds);
+}
+
+// Marks a creator as vetted, allowing them to launch campaigns while verification is required
+pub fn verify_creator(env: Env, admin: Address, creator: Address) {
+    require_admin(&env, &admin);
+    env.storage()
+        .persistent()
+        .set(&DataKey::VerifiedCreator(creator.clone()), &true);
+    env.events()
+        .publish((Symbol::new(&env, "CreatorVerified"), admin), creator);
+}
+
+// Existing campaigns are unaffected; the creator just cannot start new ones while gated
+pub fn revoke_creator(env: Env, admin: Address, creator: Address) {
+    require_admin(&env, &admin);
+    env.storage()
+        .persistent()
+        .remove(&DataKey::VerifiedCreator(creator.clone()));
+    env.events()
+        .publish((Symbol::new(&env, "CreatorRevoked"), admin), creator);
+}
+
+pub fn is_verified_creator(env: Env, creator: Address) -> bool {
+    env.storage()
+        .persistent()
+        .has(&DataKey::VerifiedCreator(creator))
+}
+
+// When enabled, only verified creators can create products
+pub fn set_require_verified_creators(env: Env, admin: Address, required: bool) {
+    require_admin(&env, &admin);
+    env.storage()
+        .instance()
+        .set(&DataKey::RequireVerifiedCreators, &required);
+}
+
+pub(crate) fn ensure_creator_allowed(env: &Env, creator: &Address) {
+    let required: bool = env
+        .storage()
+        .instance()
+        .get(&DataKey::RequireVerifiedCreators)
+        .unwrap_or(false);
+    if required && !is_verified_creator(env.clone(), creator.clone()) {
+        panic!("Creator not verified");
+    }
+}
+
+// Funding model for products created without choosing one
+pub fn set_default_funding_model(env: Env, admin: Address, model: FundingModel) {
+    require_admin(&env, &admin);
+    env.storage()
+        .instance()
+        .set(&DataKey::DefaultFundingModel, &model);
+}
+
+// Delays deadline refunds by this many seconds after a campaign fails; 0 disables the delay
+pub fn set_refund_grace_period(env: Env, admin: Address, seconds: u64) {
+    require_admin(&env, &admin);
+    env.storage()
+        .instance()
+        .set(&DataKey::RefundGracePeriod, &seconds);
+}
+
+// Caps the funding goal of new campaigns; 0 removes the cap
+pub fn set_max_funding_goal(env: Env, admin: Address, amount: u64) {
+    require_admin(&env, &admin);
+    env.storage()
+        .instance()
+        .set(&DataKey::MaxFundingGoal, &amount);
+}
+
+// Bounds the milestone list of new campaigns (DEFAULT_MAX_MILESTONES until set)
+pub fn set_max_milestones(env: Env, admin: Address, max: u32) {
+    require_admin(&env, &admin);
+    env.storage().instance().set(&DataKey::MaxMilestones, &max);
+}
+
+// Bounds the reward tier list of new campaigns (DEFAULT_MAX_REWARD_TIERS until set)
+pub fn set_max_reward_tiers(env: Env, admin: Address, max: u32) {
+    require_admin(&env, &admin);
+    env.storage().instance().set(&DataKey::MaxRewardTiers, &max);
+}
+
+// Backers of failed campaigns receive this share of their refund as a bonus, while the pool lasts
+pub fn set_failure_bonus(env: Env, admin: Address, bonus_bps: u32) {
+    require_admin(&env, &admin);
+    if bonus_bps as u64 > BPS_DENOMINATOR {
+        panic!("Bonus cannot exceed 100%");
+    }
+    env.storage()
+        .instance()
+        .set(&DataKey::FailureBonusBps, &bonus_bps);
+}
+
+// Moves `amount` of `token` from the admin into the failure bonus pool
+pub fn fund_bonus_pool(env: Env, admin: Address, token: Address, amount: u64) {
+    require_admin(&env, &admin);
+    if amount == 0 {
+        panic!("Amount must be greater than zero");
+    }
+
+    token::Client::new(&env, &token).transfer(
+        &admin,
+        &env.current_contract_address(),
+        &(amount as i128),
+    );
+    let pool_key = DataKey::BonusPool(token);
+    let pool: u64 = env.storage().instance().get(&pool_key).unwrap_or(0);
+    env.storage().instance().set(&pool_key, &(pool + amount));
+}
+
+pub fn get_bonus_pool(env: Env, token: Address) -> u64 {
+    env.storage()
+        .instance()
+        .get(&DataKey::BonusPool(token))
+        .unwrap_or(0)
+}
+
+// Recovers tokens sent to the contract outside of contributions and the bonus pool. Only the
+// balance above get_expected_balance moves, so campaign escrow and the pool are never touched.
+pub fn admin_sweep_token(env: Env, admin: Address, token: Address, to: Address) -> i128 {
+    require_admin(&env, &admin);
+
+    let client = token::Client::new(&env, &token);
+    let surplus = client.balance(&env.current_contract_address())
+        - get_expected_balance(env.clone(), token.clone());
+    if surplus <= 0 {
+        panic!("Nothing to sweep");
+    }
+    client.transfer(&env.current_contract_address(), &to, &surplus);
+
+    env.events()
+        .publish((Symbol::new(&env, "TokenSwept"), token), (to, surplus));
+    surplus
+}
+
+pub(crate) fn require_admin(env: &Env, admin: &Address) {
+    admin.require_auth();
+
+    if !get_admins(env.clone()).contains(admin) {
+        panic!("Only the admin can perform this action");
+    }
+}
+
+// Withdrawal fees are paid to the first admin in the set
+pub(crate) fn fee_recipient(env: &Env) -> Address {
+    get_admins(env.clone()).first().unwrap()
+}
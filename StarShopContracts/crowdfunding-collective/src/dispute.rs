@@ -0,0 +1,105 @@
+use crate::admin::require_admin;
+use crate::funding::{refund_unreleased, BPS_DENOMINATOR};
+use crate::product::get_product;
+use crate::tracking::merged_contributions;
+use crate::types::*;
+use soroban_sdk::{Address, Env, String, Symbol, Vec};
+
+// Open disputes backed by more than this share of the raised funds block payouts to the creator
+pub const DISPUTE_THRESHOLD_BPS: u64 = 2_500;
+
+// Backers of a funded campaign can flag that the creator is not delivering
+pub fn raise_dispute(env: Env, contributor: Address, product_id: u32, reason: String) {
+    contributor.require_auth();
+
+    let mut product = get_product(env.clone(), product_id);
+    if product.status != ProductStatus::Funded {
+        panic!("Product is not funded");
+    }
+
+    let weight = merged_contributions(&env, product_id)
+        .iter()
+        .find(|entry| entry.contributor == contributor)
+        .map(|entry| entry.amount)
+        .unwrap_or_else(|| panic!("Only contributors can raise disputes"));
+
+    let mut disputes = get_disputes(env.clone(), product_id);
+    if disputes
+        .iter()
+        .any(|dispute| dispute.contributor == contributor)
+    {
+        panic!("Dispute already raised");
+    }
+    disputes.push_back(Dispute {
+        contributor: contributor.clone(),
+        reason,
+        weight,
+        timestamp: env.ledger().timestamp(),
+    });
+    env.storage()
+        .persistent()
+        .set(&DataKey::Disputes(product_id), &disputes);
+
+    product.dispute_count += 1;
+    product.disputed_amount += weight;
+    env.storage()
+        .persistent()
+        .set(&DataKey::Products(product_id), &product);
+
+    env.events().publish(
+        (Symbol::new(&env, "DisputeRaised"), product_id, contributor),
+        weight,
+    );
+}
+
+// The admin settles the open disputes: either force a refund of the unreleased funds,
+// or clear them so payouts to the creator can continue
+pub fn resolve_dispute(env: Env, admin: Address, product_id: u32, refund: bool) {
+    require_admin(&env, &admin);
+
+    let mut product = get_product(env.clone(), product_id);
+    if product.status != ProductStatus::Funded {
+        panic!("Product is not funded");
+    }
+    if product.disputed_amount == 0 {
+        panic!("No open disputes");
+    }
+
+    product.disputed_amount = 0;
+    if refund {
+        refund_unreleased(&env, &mut product, ProductStatus::Failed);
+    } else {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Products(product_id), &product);
+    }
+
+    env.events().publish(
+        (Symbol::new(&env, "dispute_resolved"), product_id),
+        (admin, refund),
+    );
+}
+
+pub fn get_dispute_count(env: Env, product_id: u32) -> u32 {
+    get_product(env, product_id).dispute_count
+}
+
+pub fn get_disputes(env: Env, product_id: u32) -> Vec<Dispute> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Disputes(product_id))
+        .unwrap_or_else(|| Vec::new(&env))
+}
+
+pub(crate) fn ensure_not_disputed(product: &Product) {
+    if is_disputed(product) {
+        panic!("Campaign is under dispute");
+    }
+}
+
+// Payouts freeze once open disputes carry more than DISPUTE_THRESHOLD_BPS of the funds
+pub(crate) fn is_disputed(product: &Product) -> bool {
+    let disputed = product.disputed_amount as u128 * BPS_DENOMINATOR as u128;
+    let threshold = product.total_funded as u128 * DISPUTE_THRESHOLD_BPS as u128;
+    disputed > threshold
+}
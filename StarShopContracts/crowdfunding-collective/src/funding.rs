@@ -1,125 +1,907 @@
+use crate::admin::fee_recipient;
+use crate::dispute::{ensure_not_disputed, is_disputed};
+use crate::product::{
+    bump_product_ttl, get_product_or_none, set_status, MAX_PAGE_SIZE, PRODUCT_TTL_EXTEND_TO,
+    PRODUCT_TTL_THRESHOLD,
+};
+use crate::stats;
+use crate::tracking::{get_completed_milestone_count, merged_contributions};
 use crate::types::*;
-use soroban_sdk::{Address, Env, Symbol, Vec};
+use soroban_sdk::{token, Address, Env, Map, String, Symbol, Vec};
 
-pub fn contribute(env: Env, contributor: Address, product_id: u32, amount: u64) {
+// Fees are expressed in basis points of the withdrawn amount
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+// How long a funded flexible campaign's creator may go without withdrawing before backers
+// can reclaim the rest, counted from the deadline or the creator's latest withdrawal
+pub const CREATOR_WITHDRAWAL_WINDOW: u64 = 30 * 24 * 60 * 60;
+
+// Longest public message a backer can attach to a contribution, in bytes
+pub const MAX_MESSAGE_LENGTH: u32 = 280;
+
+// Auto-extending campaigns must have raised this share of their goal, in basis points
+pub const AUTO_EXTEND_THRESHOLD_BPS: u64 = 9_000;
+
+// Most times a single campaign can be auto-extended
+pub const MAX_AUTO_EXTENSIONS: u32 = 3;
+
+// Accepted token weights are per mille: a weight of 1_000 credits token amounts one to one
+pub const TOKEN_WEIGHT_UNIT: u64 = 1_000;
+
+// `tip` (0 for none) goes straight to the platform in the same token and is not escrowed or
+// counted toward the goal
+pub fn contribute(
+    env: Env,
+    contributor: Address,
+    product_id: u32,
+    amount: u64,
+    refund_to: Option<Address>,
+    message: Option<String>,
+    token: Option<Address>,
+    tip: u64,
+) {
     contributor.require_auth();
 
     let mut product = get_product(&env, product_id);
     if product.status != ProductStatus::Active {
         panic!("Product is not active");
     }
+    if product.paused {
+        panic!("Product is paused");
+    }
     if env.ledger().timestamp() > product.deadline {
         panic!("Funding period has ended");
     }
     if amount == 0 {
         panic!("Contribution must be greater than zero");
     }
+    if !product.allow_self_contribution && contributor == product.creator {
+        panic!("Creator cannot contribute to own campaign");
+    }
+    if let Some(message) = &message {
+        if message.len() > MAX_MESSAGE_LENGTH {
+            panic!("Message too long");
+        }
+    }
+    if product.contribution_cooldown > 0 {
+        let now = env.ledger().timestamp();
+        let last_key = DataKey::LastContribution(product_id, contributor.clone());
+        let last: Option<u64> = env.storage().persistent().get(&last_key);
+        if let Some(last) = last {
+            if now < last + product.contribution_cooldown {
+                panic!("Contribution cooldown active");
+            }
+        }
+        env.storage().persistent().set(&last_key, &now);
+        env.storage().persistent().extend_ttl(
+            &last_key,
+            PRODUCT_TTL_THRESHOLD,
+            PRODUCT_TTL_EXTEND_TO,
+        );
+    }
+    // Backers already in the campaign can keep topping up once the cap is reached
+    if product.max_contributors > 0 {
+        let backers = merged_contributions(&env, product_id);
+        if backers.len() >= product.max_contributors
+            && !backers.iter().any(|entry| entry.contributor == contributor)
+        {
+            panic!("Contributor limit reached");
+        }
+    }
+
+    // `amount` is in the chosen token; the goal is credited its weighted value
+    let token = token.unwrap_or_else(|| product.token.clone());
+    let weight = product
+        .accepted_tokens
+        .iter()
+        .find(|(accepted, _)| *accepted == token)
+        .map(|(_, weight)| weight)
+        .unwrap_or_else(|| panic!("Token not accepted"));
+    let mut token_amount = amount;
+    let mut amount = credited_amount(token_amount, weight);
+    if amount == 0 {
+        panic!("Contribution must be greater than zero");
+    }
 
-    // Check if contribution would exceed funding goal
+    // All-or-nothing campaigns cannot be funded past their goal. With accept_partial the
+    // contribution is trimmed to what is left and the excess never leaves the backer.
     let total_funded = env
         .storage()
-        .instance()
+        .persistent()
         .get(&DataKey::ContributionsTotal(product_id))
         .unwrap_or(0u64);
-    let new_total = total_funded + amount;
-    if new_total > product.funding_goal {
-        panic!("Contribution would exceed funding goal");
+    let all_or_nothing = product.funding_model == FundingModel::AllOrNothing;
+    if all_or_nothing && total_funded.saturating_add(amount) > product.funding_goal {
+        if !product.accept_partial {
+            panic!("Contribution would exceed funding goal");
+        }
+        let remaining = (product.funding_goal - total_funded) as u128;
+        token_amount = u64::try_from(remaining * TOKEN_WEIGHT_UNIT as u128 / weight as u128)
+            .unwrap_or_else(|_| panic!("Funding overflow"));
+        amount = credited_amount(token_amount, weight);
+        if amount == 0 {
+            panic!("Contribution would exceed funding goal");
+        }
+    }
+    // Totals never wrap: a contribution that would overflow one is rejected outright
+    let new_total = total_funded
+        .checked_add(amount)
+        .unwrap_or_else(|| panic!("Funding overflow"));
+    let new_escrowed = escrowed(&env, &token)
+        .checked_add(token_amount)
+        .unwrap_or_else(|| panic!("Funding overflow"));
+
+    // Escrow the contribution in the contract
+    token::Client::new(&env, &token).transfer(
+        &contributor,
+        &env.current_contract_address(),
+        &(token_amount as i128),
+    );
+    env.storage()
+        .instance()
+        .set(&DataKey::Escrowed(token.clone()), &new_escrowed);
+    if tip > 0 {
+        token::Client::new(&env, &token).transfer(
+            &contributor,
+            &fee_recipient(&env),
+            &(tip as i128),
+        );
     }
 
     // Update contributions
     let mut contributions: Vec<Contribution> = env
         .storage()
-        .instance()
+        .persistent()
         .get(&DataKey::Contributions(product_id))
         .unwrap_or_else(|| Vec::new(&env));
     contributions.push_back(Contribution {
         contributor: contributor.clone(),
         amount,
         timestamp: env.ledger().timestamp(),
+        refund_to,
+        message,
+        token,
+        token_amount,
     });
     env.storage()
-        .instance()
+        .persistent()
         .set(&DataKey::Contributions(product_id), &contributions);
 
     // Update total funded
     env.storage()
-        .instance()
+        .persistent()
         .set(&DataKey::ContributionsTotal(product_id), &new_total);
 
     // Update product
     product.total_funded = new_total;
-    if product.total_funded >= product.funding_goal {
-        product.status = ProductStatus::Funded;
+    stats::record_funded(&env, amount);
+    if all_or_nothing && product.total_funded >= product.funding_goal {
+        set_status(&env, &mut product, ProductStatus::Funded);
     }
     env.storage()
-        .instance()
+        .persistent()
         .set(&DataKey::Products(product_id), &product);
 
+    bump_product_ttl(&env, product_id);
+    record_contributor_history(&env, &contributor, product_id);
+
     // Emit event with explicit type annotation
     let event_data: i128 = amount as i128;
     env.events().publish(
-        (Symbol::new(&env, "Contribution"), product_id, contributor),
+        (
+            Symbol::new(&env, "Contribution"),
+            product_id,
+            contributor.clone(),
+        ),
         event_data,
     );
+    if tip > 0 {
+        env.events()
+            .publish((Symbol::new(&env, "Tip"), product_id, contributor), tip);
+    }
 }
 
-pub fn distribute_funds(env: Env, product_id: u32) {
+pub fn distribute_funds(env: Env, product_id: u32) -> DistributionReport {
     let product = get_product(&env, product_id);
     if product.status != ProductStatus::Funded {
         panic!("Product is not funded");
     }
+    ensure_not_disputed(&product);
+    if reclaim_open(&env, &product) {
+        panic!("Withdrawal window has closed");
+    }
+    if !milestones_completed(&env, product_id) {
+        panic!("Not all milestones are completed");
+    }
+
+    let mut product = get_product(&env, product_id);
+    set_status(&env, &mut product, ProductStatus::Completed);
+    if product.reward_claim_period > 0 {
+        product.reward_claim_deadline = env.ledger().timestamp() + product.reward_claim_period;
+    }
+    env.storage()
+        .persistent()
+        .set(&DataKey::Products(product_id), &product);
+
+    // Only the portion not already released through milestones is distributed
+    let amount = product.total_funded - product.released_funds;
+    pay_beneficiary(&env, &product, product.released_funds, product.total_funded);
+
+    let report = DistributionReport {
+        product_id,
+        total_raised: product.total_funded,
+        released_before: product.released_funds,
+        fee: 0,
+        distributed: amount,
+        beneficiary: product.beneficiary.clone(),
+        backer_count: merged_contributions(&env, product_id).len(),
+        milestones_completed: get_completed_milestone_count(env.clone(), product_id),
+        timestamp: env.ledger().timestamp(),
+    };
+    env.storage()
+        .persistent()
+        .set(&DataKey::DistributionReport(product_id), &report);
+    bump_product_ttl(&env, product_id);
+
+    let event_data: i128 = amount as i128;
+    env.events().publish(
+        (Symbol::new(&env, "FundsDistributed"), product_id),
+        event_data,
+    );
+    report
+}
+
+pub fn get_distribution_report(env: Env, product_id: u32) -> Option<DistributionReport> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::DistributionReport(product_id))
+}
+
+// Whether distribute_funds would succeed right now, using the same checks
+pub fn can_distribute(env: Env, product_id: u32) -> bool {
+    let product: Option<Product> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Products(product_id));
+    match product {
+        Some(product) => {
+            product.status == ProductStatus::Funded
+                && !is_disputed(&product)
+                && !reclaim_open(&env, &product)
+                && milestones_completed(&env, product_id)
+        }
+        None => false,
+    }
+}
 
+// A product created without milestones has nothing to wait on, so it is distributable as soon
+// as it is funded
+fn milestones_completed(env: &Env, product_id: u32) -> bool {
     let milestones: Vec<Milestone> = env
         .storage()
-        .instance()
+        .persistent()
+        .get(&DataKey::Milestones(product_id))
+        .unwrap_or_else(|| Vec::new(env));
+    milestones.iter().all(|milestone| milestone.completed)
+}
+
+pub fn release_milestone_funds(env: Env, product_id: u32, milestone_id: u32) {
+    let mut product = get_product(&env, product_id);
+    if product.status != ProductStatus::Funded {
+        panic!("Product is not funded");
+    }
+    ensure_not_disputed(&product);
+
+    let mut milestones: Vec<Milestone> = env
+        .storage()
+        .persistent()
         .get(&DataKey::Milestones(product_id))
         .unwrap_or_else(|| Vec::new(&env));
-    for milestone in milestones.iter() {
-        if !milestone.completed {
-            panic!("Not all milestones are completed");
-        }
+    let mut milestone = milestones
+        .get(milestone_id)
+        .unwrap_or_else(|| panic!("Milestone not found"));
+    if !milestone.completed {
+        panic!("Milestone is not completed");
+    }
+    if milestone.released {
+        panic!("Milestone funds already released");
     }
 
+    // Flexible creators may already have drawn tranches, so never release past what was raised
+    let allocation = milestone_allocation(product.total_funded, &milestones, milestone_id);
+    let amount = allocation.min(product.total_funded - product.released_funds);
+    if allocation > 0 && amount == 0 {
+        panic!("No funds left to release");
+    }
+    milestone.released = true;
+    milestones.set(milestone_id, milestone);
+    env.storage()
+        .persistent()
+        .set(&DataKey::Milestones(product_id), &milestones);
+
+    let released_before = product.released_funds;
+    product.released_funds += amount;
+    env.storage()
+        .persistent()
+        .set(&DataKey::Products(product_id), &product);
+    pay_beneficiary(&env, &product, released_before, product.released_funds);
+
+    let event_data: i128 = amount as i128;
+    env.events().publish(
+        (
+            Symbol::new(&env, "MilestoneFundsReleased"),
+            product_id,
+            milestone_id,
+        ),
+        event_data,
+    );
+}
+
+// Flexible campaigns pay the beneficiary in tranches of their choosing once the campaign has succeeded
+pub fn withdraw_creator_funds(env: Env, creator: Address, product_id: u32, amount: u64) {
+    creator.require_auth();
+
     let mut product = get_product(&env, product_id);
-    product.status = ProductStatus::Completed;
+    if product.creator != creator {
+        panic!("Only the creator can withdraw funds");
+    }
+    if product.funding_model != FundingModel::Flexible {
+        panic!("Only flexible campaigns allow partial withdrawals");
+    }
+    if product.status != ProductStatus::Funded {
+        panic!("Product is not funded");
+    }
+    ensure_not_disputed(&product);
+    if reclaim_open(&env, &product) {
+        panic!("Withdrawal window has closed");
+    }
+    if amount == 0 {
+        panic!("Amount must be greater than zero");
+    }
+    if amount > product.total_funded - product.released_funds {
+        panic!("Insufficient available funds");
+    }
+
+    let released_before = product.released_funds;
+    product.released_funds += amount;
     env.storage()
-        .instance()
+        .persistent()
         .set(&DataKey::Products(product_id), &product);
+    env.storage().persistent().set(
+        &DataKey::LastCreatorWithdrawal(product_id),
+        &env.ledger().timestamp(),
+    );
+    pay_beneficiary(&env, &product, released_before, product.released_funds);
 
-    // Emit event with explicit type annotation
-    let event_data: i128 = product.total_funded as i128;
+    let event_data: i128 = amount as i128;
     env.events().publish(
-        (Symbol::new(&env, "FundsDistributed"), product_id),
+        (Symbol::new(&env, "CreatorWithdrawal"), product_id),
         event_data,
     );
 }
 
 pub fn refund_contributors(env: Env, product_id: u32) {
-    let product = get_product(&env, product_id);
+    let cursor = refund_cursor(&env, product_id);
+    refund_window(&env, product_id, cursor, u32::MAX);
+}
+
+// Resolves every listed product whose deadline has passed while still Active.
+// Anything else is skipped, so keepers can pass a mixed batch without it reverting.
+pub fn sweep_expired(env: Env, product_ids: Vec<u32>) {
+    for product_id in product_ids.iter() {
+        if let Some(product) = get_product_or_none(env.clone(), product_id) {
+            finalize_if_due(&env, &product);
+        }
+    }
+}
+
+// Like sweep_expired, but reports the status each known product ends up in, including the ones
+// it skipped. Unknown IDs are left out. Takes at most MAX_PAGE_SIZE IDs.
+pub fn finalize_all(env: Env, product_ids: Vec<u32>) -> Vec<(u32, ProductStatus)> {
+    if product_ids.len() > MAX_PAGE_SIZE {
+        panic!("Too many product IDs");
+    }
+    let mut statuses = Vec::new(&env);
+    for product_id in product_ids.iter() {
+        if let Some(product) = get_product_or_none(env.clone(), product_id) {
+            finalize_if_due(&env, &product);
+            statuses.push_back((product_id, get_product(&env, product_id).status));
+        }
+    }
+    statuses
+}
+
+// Resolves an expired Active campaign; campaigns headed for refunds wait out the grace period
+fn finalize_if_due(env: &Env, product: &Product) {
+    if product.status != ProductStatus::Active || env.ledger().timestamp() <= product.deadline {
+        return;
+    }
+    if finalizes_as_funded(product)
+        || refund_grace_elapsed(env, product)
+        || auto_extend_due(env, product)
+    {
+        finalize(env, product.id);
+    }
+}
+
+// All-or-nothing resolution of an expired campaign: funded if it met its goal, otherwise refunded
+pub(crate) fn finalize(env: &Env, product_id: u32) {
+    let mut product = get_product(env, product_id);
+    if finalizes_as_funded(&product) {
+        set_status(env, &mut product, ProductStatus::Funded);
+        env.storage()
+            .persistent()
+            .set(&DataKey::Products(product_id), &product);
+    } else {
+        let cursor = refund_cursor(env, product_id);
+        refund_window(env, product_id, cursor, u32::MAX);
+    }
+}
+
+pub(crate) fn finalizes_as_funded(product: &Product) -> bool {
+    let flexible = product.funding_model == FundingModel::Flexible;
+    product.total_funded >= product.funding_goal || (flexible && product.total_funded > 0)
+}
+
+// Whether resolving this expired, underfunded campaign should extend it instead
+fn auto_extend_due(env: &Env, product: &Product) -> bool {
+    product.auto_extend
+        && product.auto_extensions < MAX_AUTO_EXTENSIONS
+        && env.ledger().timestamp() <= product.deadline.saturating_add(product.auto_extend_window)
+        && product.total_funded as u128 * BPS_DENOMINATOR as u128
+            >= product.funding_goal as u128 * AUTO_EXTEND_THRESHOLD_BPS as u128
+}
+
+fn auto_extend(env: &Env, product: &mut Product) {
+    product.deadline = env.ledger().timestamp() + product.auto_extend_by;
+    product.auto_extensions += 1;
+    env.storage()
+        .persistent()
+        .set(&DataKey::Products(product.id), &*product);
+
+    env.events().publish(
+        (Symbol::new(env, "AutoExtended"), product.id),
+        product.deadline,
+    );
+}
+
+// Deadline refunds open once the admin-set grace period after the deadline has passed
+fn refund_grace_elapsed(env: &Env, product: &Product) -> bool {
+    let grace: u64 = env
+        .storage()
+        .instance()
+        .get(&DataKey::RefundGracePeriod)
+        .unwrap_or(0);
+    env.ledger().timestamp() >= product.deadline.saturating_add(grace)
+}
+
+// Refunds `count` contributions starting at `start`, which must pick up where the last batch ended
+pub fn refund_batch(env: Env, product_id: u32, start: u32, count: u32) {
+    if start != refund_cursor(&env, product_id) {
+        panic!("Refund batch out of order");
+    }
+    refund_window(&env, product_id, start, count);
+}
+
+// The product only becomes Failed once the last contribution has been refunded
+fn refund_window(env: &Env, product_id: u32, start: u32, count: u32) {
+    let mut product = get_product(env, product_id);
     if product.status != ProductStatus::Active {
         panic!("Product is not active");
     }
     if env.ledger().timestamp() <= product.deadline {
         panic!("Funding period has not ended");
     }
+    // Campaigns that met their goal are funded by finalize instead
+    if product.total_funded >= product.funding_goal {
+        panic!("Funding goal was reached");
+    }
+    if product.funding_model == FundingModel::Flexible && product.total_funded > 0 {
+        panic!("Flexible campaigns keep their funds");
+    }
+    // Near-goal campaigns get more time rather than being refunded
+    if start == 0 && auto_extend_due(env, &product) {
+        auto_extend(env, &mut product);
+        return;
+    }
+    if !refund_grace_elapsed(env, &product) {
+        panic!("Refund grace period not elapsed");
+    }
 
-    let mut product = get_product(&env, product_id);
-    product.status = ProductStatus::Failed;
+    let contributions: Vec<Contribution> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Contributions(product_id))
+        .unwrap_or_else(|| Vec::new(env));
+    let end = start.saturating_add(count).min(contributions.len());
+    for i in start..end {
+        let contribution = contributions.get(i).unwrap();
+        // Deadline-failure refunds return the full amount in the token it was paid in, no fee
+        pay_out(
+            env,
+            &contribution.token,
+            &refund_address(&contribution),
+            contribution.token_amount,
+        );
+
+        // Emit event with explicit type annotation
+        let event_data: i128 = contribution.token_amount as i128;
+        env.events().publish(
+            (
+                Symbol::new(env, "Refund"),
+                product_id,
+                contribution.contributor.clone(),
+            ),
+            event_data,
+        );
+
+        pay_failure_bonus(env, &product, &contribution);
+    }
+
+    if end < contributions.len() {
+        env.storage()
+            .persistent()
+            .set(&DataKey::RefundCursor(product_id), &end);
+        return;
+    }
+
+    set_status(env, &mut product, ProductStatus::Failed);
     env.storage()
-        .instance()
+        .persistent()
         .set(&DataKey::Products(product_id), &product);
+    env.storage().persistent().set(
+        &DataKey::Contributions(product_id),
+        &Vec::<Contribution>::new(env),
+    );
+    env.storage()
+        .persistent()
+        .set(&DataKey::ContributionsTotal(product_id), &0u64);
+    env.storage()
+        .persistent()
+        .remove(&DataKey::RefundCursor(product_id));
+}
 
-    let contributions: Vec<Contribution> = env
+// Compensation on top of a failed campaign's refund, paid from the admin-funded pool for the
+// contribution's token. Skipped when the pool cannot cover it.
+fn pay_failure_bonus(env: &Env, product: &Product, contribution: &Contribution) {
+    let bonus_bps: u32 = env
         .storage()
         .instance()
+        .get(&DataKey::FailureBonusBps)
+        .unwrap_or(0);
+    let bonus =
+        (contribution.token_amount as u128 * bonus_bps as u128 / BPS_DENOMINATOR as u128) as u64;
+    let pool_key = DataKey::BonusPool(contribution.token.clone());
+    let pool: u64 = env.storage().instance().get(&pool_key).unwrap_or(0);
+    if bonus == 0 || pool < bonus {
+        return;
+    }
+
+    env.storage().instance().set(&pool_key, &(pool - bonus));
+    transfer_out(
+        env,
+        &contribution.token,
+        &refund_address(contribution),
+        bonus,
+    );
+
+    let event_data: i128 = bonus as i128;
+    env.events().publish(
+        (
+            Symbol::new(env, "RefundBonus"),
+            product.id,
+            contribution.contributor.clone(),
+        ),
+        event_data,
+    );
+}
+
+fn refund_cursor(env: &Env, product_id: u32) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::RefundCursor(product_id))
+        .unwrap_or(0)
+}
+
+// Backers can reclaim the unreleased balance once a milestone misses its target date
+pub fn refund_remaining(env: Env, product_id: u32) {
+    let mut product = get_product(&env, product_id);
+    if product.status != ProductStatus::Funded {
+        panic!("Product is not funded");
+    }
+
+    if !milestone_missed(&env, product_id) {
+        panic!("No missed milestone");
+    }
+
+    refund_unreleased(&env, &mut product, ProductStatus::Failed);
+}
+
+// Anyone can fail a funded campaign once the given milestone is past its target date without
+// being completed, refunding the unreleased balance without waiting for anything else
+pub fn trigger_milestone_refund(env: Env, product_id: u32, milestone_id: u32) {
+    let mut product = get_product(&env, product_id);
+    if product.status != ProductStatus::Funded {
+        panic!("Product is not funded");
+    }
+
+    let milestones: Vec<Milestone> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Milestones(product_id))
+        .unwrap_or_else(|| Vec::new(&env));
+    let milestone = milestones
+        .get(milestone_id)
+        .unwrap_or_else(|| panic!("Milestone not found"));
+    if milestone.completed {
+        panic!("Milestone already completed");
+    }
+    if env.ledger().timestamp() <= milestone.target_date {
+        panic!("Milestone target date not passed");
+    }
+
+    refund_unreleased(&env, &mut product, ProductStatus::MilestoneFailed);
+    env.events().publish(
+        (Symbol::new(&env, "MilestoneFailed"), product_id),
+        milestone_id,
+    );
+}
+
+// The creator can call off a campaign that is still collecting before its deadline, or a funded
+// one, refunding whatever has not been released. Flexible campaigns whose backers can already
+// reclaim are left to reclaim_unwithdrawn.
+pub fn cancel_product(env: Env, creator: Address, product_id: u32) {
+    creator.require_auth();
+
+    let mut product = get_product(&env, product_id);
+    if product.creator != creator {
+        panic!("Only the creator can cancel");
+    }
+    let cancellable = match product.status {
+        ProductStatus::Active => env.ledger().timestamp() <= product.deadline,
+        ProductStatus::Funded => !reclaim_open(&env, &product),
+        _ => false,
+    };
+    if !cancellable {
+        panic!("Product cannot be cancelled");
+    }
+
+    refund_unreleased(&env, &mut product, ProductStatus::Cancelled);
+    env.events().publish(
+        (Symbol::new(&env, "ProductCancelled"), product_id),
+        product.total_funded - product.released_funds,
+    );
+}
+
+// What the contributor would get back right now, as (token, amount) pairs in the tokens they paid
+// in: exactly what withdraw_contribution would pay while withdrawals are open, in full once the
+// campaign has failed to reach its goal, or their share of the unreleased funds after a missed
+// milestone or an idle flexible creator. Empty when nothing is refundable.
+pub fn get_refundable_amount(
+    env: Env,
+    product_id: u32,
+    contributor: Address,
+) -> Vec<(Address, u64)> {
+    let product = get_product(&env, product_id);
+    let contributions: Vec<Contribution> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Contributions(product_id))
+        .unwrap_or_else(|| Vec::new(&env));
+
+    let mut amounts: Map<Address, u64> = Map::new(&env);
+    if withdrawal_open(&env, &product) {
+        for (token, (payout, _)) in withdrawal_payouts(&env, &contributions, &contributor).iter() {
+            amounts.set(token, payout);
+        }
+    } else {
+        // The credited share each entry would get back, converted to its token below the same way
+        // refund_window, reclaim_unwithdrawn and refund_unreleased pay it
+        let mut shares: Vec<u64> = Vec::new(&env);
+        match product.status {
+            ProductStatus::Active if deadline_refund_open(&env, &product) => {
+                // Entries before the cursor were already refunded by an earlier batch
+                let cursor = refund_cursor(&env, product_id);
+                for (i, contribution) in contributions.iter().enumerate() {
+                    shares.push_back(if (i as u32) < cursor {
+                        0
+                    } else {
+                        contribution.amount
+                    });
+                }
+            }
+            // A backer who reclaimed has had their share of the unreleased funds already
+            ProductStatus::Funded if has_reclaimed(&env, product_id, &contributor) => {}
+            ProductStatus::Funded if reclaim_open(&env, &product) => {
+                let remaining = product.total_funded - product.released_funds;
+                for contribution in contributions.iter() {
+                    shares.push_back(proportional_share(
+                        contribution.amount,
+                        remaining,
+                        product.total_funded,
+                    ));
+                }
+            }
+            ProductStatus::Funded if milestone_missed(&env, product_id) => {
+                shares = unreleased_shares(&product, &contributions);
+            }
+            _ => {}
+        }
+        for (contribution, share) in contributions.iter().zip(shares.iter()) {
+            if contribution.contributor == contributor {
+                let paid = amounts.get(contribution.token.clone()).unwrap_or(0);
+                amounts.set(
+                    contribution.token.clone(),
+                    paid + token_share(&contribution, share),
+                );
+            }
+        }
+    }
+
+    let mut refundable = Vec::new(&env);
+    for (token, amount) in amounts.iter() {
+        if amount > 0 {
+            refundable.push_back((token, amount));
+        }
+    }
+    refundable
+}
+
+// What refund_contributors or refund_remaining would return to all backers right now, in
+// credited units and without failure bonuses. 0 when neither can run.
+pub fn get_total_refundable(env: Env, product_id: u32) -> u64 {
+    let product = get_product(&env, product_id);
+    match product.status {
+        ProductStatus::Active if deadline_refund_open(&env, &product) => {
+            let contributions: Vec<Contribution> = env
+                .storage()
+                .persistent()
+                .get(&DataKey::Contributions(product_id))
+                .unwrap_or_else(|| Vec::new(&env));
+            let cursor = refund_cursor(&env, product_id).min(contributions.len());
+            contributions
+                .slice(cursor..contributions.len())
+                .iter()
+                .map(|c| c.amount)
+                .sum()
+        }
+        ProductStatus::Funded if milestone_missed(&env, product_id) => {
+            let contributions: Vec<Contribution> = env
+                .storage()
+                .persistent()
+                .get(&DataKey::Contributions(product_id))
+                .unwrap_or_else(|| Vec::new(&env));
+            let shares = unreleased_shares(&product, &contributions);
+            contributions
+                .iter()
+                .zip(shares.iter())
+                .filter(|(c, _)| !has_reclaimed(&env, product_id, &c.contributor))
+                .map(|(_, share)| share)
+                .sum()
+        }
+        _ => 0,
+    }
+}
+
+// The conditions refund_window enforces before refunding an expired campaign
+fn deadline_refund_open(env: &Env, product: &Product) -> bool {
+    env.ledger().timestamp() > product.deadline
+        && !finalizes_as_funded(product)
+        && refund_grace_elapsed(env, product)
+        && !(refund_cursor(env, product.id) == 0 && auto_extend_due(env, product))
+}
+
+// Once a flexible campaign's creator has been idle for CREATOR_WITHDRAWAL_WINDOW, each backer
+// can take back their share of the funds the creator left unwithdrawn. The creator can no longer
+// withdraw at that point, so released_funds is fixed and every backer's share stays the same.
+pub fn reclaim_unwithdrawn(env: Env, contributor: Address, product_id: u32) {
+    contributor.require_auth();
+
+    let product = get_product(&env, product_id);
+    if product.funding_model != FundingModel::Flexible {
+        panic!("Only flexible campaigns can be reclaimed");
+    }
+    if product.status != ProductStatus::Funded {
+        panic!("Product is not funded");
+    }
+    if !reclaim_open(&env, &product) {
+        panic!("Withdrawal window still open");
+    }
+    if has_reclaimed(&env, product_id, &contributor) {
+        panic!("Already reclaimed");
+    }
+
+    let contributions: Vec<Contribution> = env
+        .storage()
+        .persistent()
         .get(&DataKey::Contributions(product_id))
         .unwrap_or_else(|| Vec::new(&env));
+    let remaining = product.total_funded - product.released_funds;
+    let mut share = 0u64;
+    let mut found = false;
     for contribution in contributions.iter() {
-        // Emit event with explicit type annotation
-        let event_data: i128 = contribution.amount as i128;
+        if contribution.contributor == contributor {
+            found = true;
+            let entry_share =
+                proportional_share(contribution.amount, remaining, product.total_funded);
+            share += entry_share;
+            pay_out(
+                &env,
+                &contribution.token,
+                &refund_address(&contribution),
+                token_share(&contribution, entry_share),
+            );
+        }
+    }
+    if !found {
+        panic!("No contributions found for this contributor");
+    }
+    let reclaimed_key = DataKey::Reclaimed(product_id, contributor.clone());
+    env.storage().persistent().set(&reclaimed_key, &true);
+    env.storage().persistent().extend_ttl(
+        &reclaimed_key,
+        PRODUCT_TTL_THRESHOLD,
+        PRODUCT_TTL_EXTEND_TO,
+    );
+
+    let event_data: i128 = share as i128;
+    env.events().publish(
+        (Symbol::new(&env, "Reclaim"), product_id, contributor),
+        event_data,
+    );
+}
+
+fn reclaim_open(env: &Env, product: &Product) -> bool {
+    if product.funding_model != FundingModel::Flexible || product.status != ProductStatus::Funded {
+        return false;
+    }
+    let last_withdrawal: u64 = env
+        .storage()
+        .persistent()
+        .get(&DataKey::LastCreatorWithdrawal(product.id))
+        .unwrap_or(0);
+    let idle_since = product.deadline.max(last_withdrawal);
+    env.ledger().timestamp() > idle_since.saturating_add(CREATOR_WITHDRAWAL_WINDOW)
+}
+
+fn has_reclaimed(env: &Env, product_id: u32, contributor: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .has(&DataKey::Reclaimed(product_id, contributor.clone()))
+}
+
+// Fails a funded campaign and returns whatever has not been released to the creator,
+// split in proportion to each backer's contribution. Backers who already reclaimed their share
+// from an idle flexible creator are skipped.
+pub(crate) fn refund_unreleased(env: &Env, product: &mut Product, status: ProductStatus) {
+    let product_id = product.id;
+    set_status(env, product, status);
+    env.storage()
+        .persistent()
+        .set(&DataKey::Products(product_id), &*product);
+
+    let contributions: Vec<Contribution> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Contributions(product_id))
+        .unwrap_or_else(|| Vec::new(env));
+    let shares = unreleased_shares(product, &contributions);
+    for (contribution, share) in contributions.iter().zip(shares.iter()) {
+        if has_reclaimed(env, product_id, &contribution.contributor) {
+            continue;
+        }
+        let token_share = token_share(&contribution, share);
+        pay_out(
+            env,
+            &contribution.token,
+            &refund_address(&contribution),
+            token_share,
+        );
+
+        let event_data: i128 = token_share as i128;
         env.events().publish(
             (
-                Symbol::new(&env, "Refund"),
+                Symbol::new(env, "Refund"),
                 product_id,
                 contribution.contributor,
             ),
@@ -127,18 +909,334 @@ pub fn refund_contributors(env: Env, product_id: u32) {
         );
     }
 
-    env.storage().instance().set(
+    env.storage().persistent().set(
         &DataKey::Contributions(product_id),
-        &Vec::<Contribution>::new(&env),
+        &Vec::<Contribution>::new(env),
     );
     env.storage()
-        .instance()
+        .persistent()
         .set(&DataKey::ContributionsTotal(product_id), &0u64);
 }
 
-fn get_product(env: &Env, product_id: u32) -> Product {
+// Voluntarily pull a contribution back out of an active campaign, minus the withdrawal fee.
+// All-or-nothing campaigns lock in once funded. Overfunding and flexible campaigns closed early
+// stay withdrawable until the deadline while nothing has been paid out, and a withdrawal that
+// takes them back below the goal returns them to Active.
+pub fn withdraw_contribution(env: Env, contributor: Address, product_id: u32) {
+    contributor.require_auth();
+
+    let mut product = get_product(&env, product_id);
+    let closed_early = closed_early(&product);
+    if product.status != ProductStatus::Active && !closed_early {
+        panic!("Product is not active");
+    }
+    if env.ledger().timestamp() > product.deadline {
+        panic!("Funding period has ended");
+    }
+    if closed_early && product.disputed_amount > 0 {
+        panic!("Campaign is under dispute");
+    }
+
+    let contributions: Vec<Contribution> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Contributions(product_id))
+        .unwrap_or_else(|| Vec::new(&env));
+    let payouts = withdrawal_payouts(&env, &contributions, &contributor);
+    let mut remaining_contributions = Vec::new(&env);
+    let mut amount = 0u64;
+    let mut recipient = contributor.clone();
+    for contribution in contributions.iter() {
+        if contribution.contributor == contributor {
+            amount += contribution.amount;
+            // The most recent contribution's refund address wins
+            recipient = refund_address(&contribution);
+        } else {
+            remaining_contributions.push_back(contribution);
+        }
+    }
+    if amount == 0 {
+        panic!("No contributions found for this contributor");
+    }
+
+    env.storage().persistent().set(
+        &DataKey::Contributions(product_id),
+        &remaining_contributions,
+    );
+    product.total_funded -= amount;
+    stats::record_unfunded(&env, amount);
+    env.storage().persistent().set(
+        &DataKey::ContributionsTotal(product_id),
+        &product.total_funded,
+    );
+    if product.status == ProductStatus::Funded && product.total_funded < product.funding_goal {
+        set_status(&env, &mut product, ProductStatus::Active);
+    }
+    env.storage()
+        .persistent()
+        .set(&DataKey::Products(product_id), &product);
+
+    // Each token is returned separately, with the fee taken in that token
+    for (token, (payout, fee)) in payouts.iter() {
+        if fee > 0 {
+            pay_out(&env, &token, &fee_recipient(&env), fee);
+        }
+        pay_out(&env, &token, &recipient, payout);
+
+        let event_data: i128 = payout as i128;
+        env.events().publish(
+            (
+                Symbol::new(&env, "Withdrawal"),
+                product_id,
+                contributor.clone(),
+            ),
+            event_data,
+        );
+    }
+}
+
+// A flexible or overfunding campaign closed early through close_funding, before anything was
+// released; backers can still withdraw until the deadline
+fn closed_early(product: &Product) -> bool {
+    product.status == ProductStatus::Funded
+        && product.funding_model != FundingModel::AllOrNothing
+        && product.released_funds == 0
+}
+
+// Whether withdraw_contribution would currently accept a withdrawal from this product
+fn withdrawal_open(env: &Env, product: &Product) -> bool {
+    let open = product.status == ProductStatus::Active
+        || (closed_early(product) && product.disputed_amount == 0);
+    open && env.ledger().timestamp() <= product.deadline
+}
+
+// What withdraw_contribution returns per token, as (payout, fee) with the fee taken in that token
+fn withdrawal_payouts(
+    env: &Env,
+    contributions: &Vec<Contribution>,
+    contributor: &Address,
+) -> Map<Address, (u64, u64)> {
+    let mut token_amounts: Map<Address, u64> = Map::new(env);
+    for contribution in contributions.iter() {
+        if contribution.contributor == *contributor {
+            let paid = token_amounts.get(contribution.token.clone()).unwrap_or(0);
+            token_amounts.set(contribution.token.clone(), paid + contribution.token_amount);
+        }
+    }
+    let mut payouts = Map::new(env);
+    for (token, token_amount) in token_amounts.iter() {
+        let fee = withdraw_fee(env, token_amount);
+        payouts.set(token, (token_amount - fee, fee));
+    }
+    payouts
+}
+
+// Append-only: products stay in a backer's history after withdrawals and refunds
+pub fn get_contributor_history(env: Env, contributor: Address) -> Vec<u32> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::ContributorProducts(contributor))
+        .unwrap_or_else(|| Vec::new(&env))
+}
+
+fn record_contributor_history(env: &Env, contributor: &Address, product_id: u32) {
+    let key = DataKey::ContributorProducts(contributor.clone());
+    let mut product_ids = get_contributor_history(env.clone(), contributor.clone());
+    if !product_ids.contains(product_id) {
+        product_ids.push_back(product_id);
+        env.storage().persistent().set(&key, &product_ids);
+    }
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, PRODUCT_TTL_THRESHOLD, PRODUCT_TTL_EXTEND_TO);
+}
+
+pub(crate) fn withdraw_fee(env: &Env, amount: u64) -> u64 {
+    let fee_bps: u32 = env
+        .storage()
+        .instance()
+        .get(&DataKey::WithdrawFeeBps)
+        .unwrap_or(0);
+    (amount as u128 * fee_bps as u128 / BPS_DENOMINATOR as u128) as u64
+}
+
+fn refund_address(contribution: &Contribution) -> Address {
+    contribution
+        .refund_to
+        .clone()
+        .unwrap_or_else(|| contribution.contributor.clone())
+}
+
+// Pays campaign funds out of escrow
+fn pay_out(env: &Env, token: &Address, to: &Address, amount: u64) {
+    if amount == 0 {
+        return;
+    }
+    let escrowed = escrowed(env, token);
+    env.storage()
+        .instance()
+        .set(&DataKey::Escrowed(token.clone()), &(escrowed - amount));
+    transfer_out(env, token, to, amount);
+}
+
+fn transfer_out(env: &Env, token: &Address, to: &Address, amount: u64) {
+    token::Client::new(env, token).transfer(&env.current_contract_address(), to, &(amount as i128));
+}
+
+fn escrowed(env: &Env, token: &Address) -> u64 {
     env.storage()
         .instance()
+        .get(&DataKey::Escrowed(token.clone()))
+        .unwrap_or(0)
+}
+
+// What the contract should hold in `token`: campaign funds not yet paid out plus the failure
+// bonus pool. Maintained as funds move, so it can be compared against the token's balance.
+pub fn get_expected_balance(env: Env, token: Address) -> i128 {
+    let pool: u64 = env
+        .storage()
+        .instance()
+        .get(&DataKey::BonusPool(token.clone()))
+        .unwrap_or(0);
+    escrowed(&env, &token) as i128 + pool as i128
+}
+
+// (released, remaining): funds paid out to the beneficiary and funds still escrowed for the
+// product, in credited units. Refunds and reclaims reduce what remains without being released.
+pub fn get_fund_status(env: Env, product_id: u32) -> (u64, u64) {
+    let product = get_product(&env, product_id);
+    let contributions: Vec<Contribution> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Contributions(product_id))
+        .unwrap_or_else(|| Vec::new(&env));
+    match product.status {
+        // A refund running in batches has already returned everything before the cursor
+        ProductStatus::Active => {
+            let refunded: u64 = contributions
+                .slice(0..refund_cursor(&env, product_id).min(contributions.len()))
+                .iter()
+                .map(|c| c.amount)
+                .sum();
+            (0, product.total_funded - refunded)
+        }
+        ProductStatus::Funded => {
+            let unreleased = product.total_funded - product.released_funds;
+            let reclaimed: u64 = contributions
+                .iter()
+                .filter(|c| has_reclaimed(&env, product_id, &c.contributor))
+                .map(|c| proportional_share(c.amount, unreleased, product.total_funded))
+                .sum();
+            (product.released_funds, unreleased - reclaimed)
+        }
+        ProductStatus::Completed => (product.total_funded, 0),
+        _ => (product.released_funds, 0),
+    }
+}
+
+fn credited_amount(token_amount: u64, weight: u64) -> u64 {
+    u64::try_from(token_amount as u128 * weight as u128 / TOKEN_WEIGHT_UNIT as u128)
+        .unwrap_or_else(|_| panic!("Funding overflow"))
+}
+
+// A share of a contribution's credited amount, converted back into the token it was paid in
+fn token_share(contribution: &Contribution, share: u64) -> u64 {
+    if share == contribution.amount {
+        return contribution.token_amount;
+    }
+    proportional_share(contribution.token_amount, share, contribution.amount)
+}
+
+// Pays the beneficiary as released_funds moves from `released_before` to `released_after`.
+// Every token raised is released in the same proportion, computed from the cumulative release
+// so rounding never accumulates and the final payout empties each token.
+fn pay_beneficiary(env: &Env, product: &Product, released_before: u64, released_after: u64) {
+    let contributions: Vec<Contribution> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Contributions(product.id))
+        .unwrap_or_else(|| Vec::new(env));
+    let mut raised: Map<Address, u64> = Map::new(env);
+    for contribution in contributions.iter() {
+        let total = raised.get(contribution.token.clone()).unwrap_or(0);
+        raised.set(
+            contribution.token.clone(),
+            total + contribution.token_amount,
+        );
+    }
+    for (token, token_raised) in raised.iter() {
+        let before = proportional_share(token_raised, released_before, product.total_funded);
+        let after = proportional_share(token_raised, released_after, product.total_funded);
+        pay_out(env, &token, &product.beneficiary, after - before);
+    }
+}
+
+// Split by milestone weight, with the last milestone taking the rounding remainder
+pub(crate) fn milestone_allocation(
+    total_funded: u64,
+    milestones: &Vec<Milestone>,
+    milestone_id: u32,
+) -> u64 {
+    let total_weight: u128 = milestones
+        .iter()
+        .map(|milestone| milestone.weight as u128)
+        .sum();
+    if total_weight == 0 {
+        return 0;
+    }
+    let share = |weight: u32| (total_funded as u128 * weight as u128 / total_weight) as u64;
+    if milestone_id == milestones.len() - 1 {
+        let others: u64 = milestones
+            .iter()
+            .take(milestone_id as usize)
+            .map(|milestone| share(milestone.weight))
+            .sum();
+        total_funded - others
+    } else {
+        share(milestones.get(milestone_id).unwrap().weight)
+    }
+}
+
+fn milestone_missed(env: &Env, product_id: u32) -> bool {
+    let now = env.ledger().timestamp();
+    let milestones: Vec<Milestone> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Milestones(product_id))
+        .unwrap_or_else(|| Vec::new(env));
+    milestones
+        .iter()
+        .any(|milestone| !milestone.completed && now > milestone.target_date)
+}
+
+// Each contribution's share of the funds not yet released to the creator
+fn unreleased_shares(product: &Product, contributions: &Vec<Contribution>) -> Vec<u64> {
+    let remaining = product.total_funded - product.released_funds;
+    let mut shares = Vec::new(contributions.env());
+    let mut refunded = 0u64;
+    for (i, contribution) in contributions.iter().enumerate() {
+        // The last refund absorbs any rounding remainder so the full balance is returned
+        let share = if i as u32 == contributions.len() - 1 {
+            remaining - refunded
+        } else {
+            proportional_share(contribution.amount, remaining, product.total_funded)
+        };
+        refunded += share;
+        shares.push_back(share);
+    }
+    shares
+}
+
+fn proportional_share(amount: u64, remaining: u64, total_funded: u64) -> u64 {
+    if total_funded == 0 {
+        return 0;
+    }
+    (amount as u128 * remaining as u128 / total_funded as u128) as u64
+}
+
+fn get_product(env: &Env, product_id: u32) -> Product {
+    env.storage()
+        .persistent()
         .get(&DataKey::Products(product_id))
         .unwrap_or_else(|| panic!("Product not found"))
 }
@@ -0,0 +1,23 @@
+use soroban_sdk::Env;
+
+use crate::types::DataKey;
+
+// Reentrancy lock for entry points that move tokens.
+//
+// The Soroban host already rejects a contract calling back into itself, so a token
+// that tries to reenter `contribute` or a refund from inside `transfer` fails today.
+// Products can use any token contract, though, so this lock keeps the invariant
+// explicit in our own code rather than relying on host behavior alone. A panic rolls
+// back every storage write, so a failed call can never leave the lock held.
+
+pub(crate) fn lock(env: &Env) {
+    let storage = env.storage().temporary();
+    if storage.has(&DataKey::ReentrancyLock) {
+        panic!("Reentrant call");
+    }
+    storage.set(&DataKey::ReentrancyLock, &true);
+}
+
+pub(crate) fn unlock(env: &Env) {
+    env.storage().temporary().remove(&DataKey::ReentrancyLock);
+}
@@ -1,15 +1,25 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, Address, Env, String, Vec};
+// Several entry points take more than clippy's seven arguments, and the client and args code
+// #[contractimpl] generates for them cannot carry a per-function allow
+#![allow(clippy::too_many_arguments)]
+use soroban_sdk::{contract, contractimpl, Address, BytesN, Env, String, Symbol, Vec};
 
+mod admin;
+mod dispute;
 mod funding;
+mod guard;
 mod product;
 mod rewards;
+mod stats;
 mod tracking;
 mod types;
 
+pub use admin::*;
+pub use dispute::*;
 pub use funding::*;
 pub use product::*;
 pub use rewards::*;
+pub use stats::*;
 pub use tracking::*;
 pub use types::*;
 
@@ -23,45 +33,414 @@ pub struct CrowdfundingCollective;
 impl CrowdfundingCollective {
     // Initialize the contract
     pub fn initialize(env: Env, admin: Address) {
+        // A second call would replace the admin set and restart product IDs
+        if env.storage().instance().has(&DataKey::Admins) {
+            panic!("Already initialized");
+        }
         admin.require_auth();
-        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage()
+            .instance()
+            .set(&DataKey::Admins, &Vec::from_array(&env, [admin]));
         env.storage().instance().set(&DataKey::NextProductId, &1u32);
     }
 
+    pub fn add_admin(env: Env, caller: Address, new_admin: Address) {
+        admin::add_admin(env, caller, new_admin)
+    }
+
+    pub fn remove_admin(env: Env, caller: Address, target: Address) {
+        admin::remove_admin(env, caller, target)
+    }
+
+    pub fn get_admins(env: Env) -> Vec<Address> {
+        admin::get_admins(env)
+    }
+
+    // Replace the contract code, e.g. to ship bug fixes
+    pub fn upgrade(env: Env, admin: Address, new_wasm_hash: BytesN<32>) {
+        admin::upgrade(env, admin, new_wasm_hash)
+    }
+
+    pub fn get_version(env: Env) -> u32 {
+        admin::get_version(env)
+    }
+
+    pub fn get_config(env: Env) -> ContractConfig {
+        admin::get_config(env)
+    }
+
+    pub fn set_token(env: Env, admin: Address, token: Address) {
+        admin::set_token(env, admin, token)
+    }
+
+    pub fn set_native_token(env: Env, admin: Address, token: Address) {
+        admin::set_native_token(env, admin, token)
+    }
+
+    pub fn set_withdraw_fee(env: Env, admin: Address, fee_bps: u32) {
+        admin::set_withdraw_fee(env, admin, fee_bps)
+    }
+
+    pub fn set_min_campaign_duration(env: Env, admin: Address, seconds: u64) {
+        admin::set_min_campaign_duration(env, admin, seconds)
+    }
+
+    pub fn verify_creator(env: Env, admin: Address, creator: Address) {
+        admin::verify_creator(env, admin, creator)
+    }
+
+    pub fn revoke_creator(env: Env, admin: Address, creator: Address) {
+        admin::revoke_creator(env, admin, creator)
+    }
+
+    pub fn is_verified_creator(env: Env, creator: Address) -> bool {
+        admin::is_verified_creator(env, creator)
+    }
+
+    pub fn set_require_verified_creators(env: Env, admin: Address, required: bool) {
+        admin::set_require_verified_creators(env, admin, required)
+    }
+
+    pub fn set_default_funding_model(env: Env, admin: Address, model: FundingModel) {
+        admin::set_default_funding_model(env, admin, model)
+    }
+
+    pub fn set_refund_grace_period(env: Env, admin: Address, seconds: u64) {
+        admin::set_refund_grace_period(env, admin, seconds)
+    }
+
+    pub fn set_max_funding_goal(env: Env, admin: Address, amount: u64) {
+        admin::set_max_funding_goal(env, admin, amount)
+    }
+
+    pub fn set_max_milestones(env: Env, admin: Address, max: u32) {
+        admin::set_max_milestones(env, admin, max)
+    }
+
+    pub fn set_max_reward_tiers(env: Env, admin: Address, max: u32) {
+        admin::set_max_reward_tiers(env, admin, max)
+    }
+
+    pub fn set_failure_bonus(env: Env, admin: Address, bonus_bps: u32) {
+        admin::set_failure_bonus(env, admin, bonus_bps)
+    }
+
+    pub fn fund_bonus_pool(env: Env, admin: Address, token: Address, amount: u64) {
+        admin::fund_bonus_pool(env, admin, token, amount)
+    }
+
+    pub fn get_bonus_pool(env: Env, token: Address) -> u64 {
+        admin::get_bonus_pool(env, token)
+    }
+
+    pub fn admin_sweep_token(env: Env, admin: Address, token: Address, to: Address) -> i128 {
+        guard::lock(&env);
+        let swept = admin::admin_sweep_token(env.clone(), admin, token, to);
+        guard::unlock(&env);
+        swept
+    }
+
     // Product functions
     pub fn create_product(
         env: Env,
         creator: Address,
         name: String,
         description: String,
+        metadata_uri: Option<String>,
         funding_goal: u64,
         deadline: u64, // Changed from &u64
         reward_tiers: Vec<RewardTier>,
         milestones: Vec<Milestone>,
+        category: Symbol,
+        funding_model: Option<FundingModel>,
     ) -> u32 {
         product::create_product(
             env,
             creator,
             name,
             description,
+            metadata_uri,
+            funding_goal,
+            deadline,
+            reward_tiers,
+            milestones,
+            category,
+            funding_model,
+        )
+    }
+
+    pub fn create_native_product(
+        env: Env,
+        creator: Address,
+        name: String,
+        description: String,
+        metadata_uri: Option<String>,
+        funding_goal: u64,
+        deadline: u64,
+        reward_tiers: Vec<RewardTier>,
+        milestones: Vec<Milestone>,
+        category: Symbol,
+        funding_model: Option<FundingModel>,
+    ) -> u32 {
+        product::create_native_product(
+            env,
+            creator,
+            name,
+            description,
+            metadata_uri,
             funding_goal,
             deadline,
             reward_tiers,
             milestones,
+            category,
+            funding_model,
         )
     }
 
+    pub fn update_product_details(
+        env: Env,
+        creator: Address,
+        product_id: u32,
+        name: String,
+        description: String,
+        metadata_uri: String,
+    ) {
+        product::update_product_details(env, creator, product_id, name, description, metadata_uri)
+    }
+
+    pub fn update_funding_goal(env: Env, creator: Address, product_id: u32, new_goal: u64) {
+        product::update_funding_goal(env, creator, product_id, new_goal)
+    }
+
+    pub fn set_milestone_date_enforcement(
+        env: Env,
+        creator: Address,
+        product_id: u32,
+        enforce: bool,
+    ) {
+        product::set_milestone_date_enforcement(env, creator, product_id, enforce)
+    }
+
+    pub fn set_contribution_cooldown(env: Env, creator: Address, product_id: u32, seconds: u64) {
+        product::set_contribution_cooldown(env, creator, product_id, seconds)
+    }
+
+    pub fn set_max_contributors(env: Env, creator: Address, product_id: u32, max: u32) {
+        product::set_max_contributors(env, creator, product_id, max)
+    }
+
+    pub fn pause_product(env: Env, creator: Address, product_id: u32) {
+        product::pause_product(env, creator, product_id)
+    }
+
+    pub fn resume_product(env: Env, creator: Address, product_id: u32) {
+        product::resume_product(env, creator, product_id)
+    }
+
+    pub fn set_allow_self_contribution(env: Env, creator: Address, product_id: u32, allowed: bool) {
+        product::set_allow_self_contribution(env, creator, product_id, allowed)
+    }
+
+    pub fn set_reward_strategy(
+        env: Env,
+        creator: Address,
+        product_id: u32,
+        strategy: RewardStrategy,
+    ) {
+        product::set_reward_strategy(env, creator, product_id, strategy)
+    }
+
+    pub fn set_reward_claim_period(env: Env, creator: Address, product_id: u32, seconds: u64) {
+        product::set_reward_claim_period(env, creator, product_id, seconds)
+    }
+
+    pub fn set_auto_extend(
+        env: Env,
+        creator: Address,
+        product_id: u32,
+        enabled: bool,
+        window: u64,
+        extend_by: u64,
+    ) {
+        product::set_auto_extend(env, creator, product_id, enabled, window, extend_by)
+    }
+
+    pub fn set_accept_partial(env: Env, creator: Address, product_id: u32, enabled: bool) {
+        product::set_accept_partial(env, creator, product_id, enabled)
+    }
+
+    pub fn set_anonymous(env: Env, creator: Address, product_id: u32, enabled: bool) {
+        product::set_anonymous(env, creator, product_id, enabled)
+    }
+
+    pub fn set_accepted_tokens(
+        env: Env,
+        creator: Address,
+        product_id: u32,
+        tokens: Vec<(Address, u64)>,
+    ) {
+        product::set_accepted_tokens(env, creator, product_id, tokens)
+    }
+
+    pub fn update_beneficiary(
+        env: Env,
+        creator: Address,
+        product_id: u32,
+        new_beneficiary: Address,
+    ) {
+        product::update_beneficiary(env, creator, product_id, new_beneficiary)
+    }
+
+    pub fn transfer_product_ownership(
+        env: Env,
+        current_creator: Address,
+        product_id: u32,
+        new_creator: Address,
+    ) {
+        product::transfer_product_ownership(env, current_creator, product_id, new_creator)
+    }
+
+    pub fn set_funding_model(env: Env, creator: Address, product_id: u32, model: FundingModel) {
+        product::set_funding_model(env, creator, product_id, model)
+    }
+
+    pub fn close_funding(env: Env, creator: Address, product_id: u32) {
+        product::close_funding(env, creator, product_id)
+    }
+
+    pub fn reopen_product(env: Env, creator: Address, product_id: u32, new_deadline: u64) {
+        product::reopen_product(env, creator, product_id, new_deadline)
+    }
+
+    pub fn extend_product_ttl(env: Env, product_id: u32, ledgers: u32) {
+        product::extend_product_ttl(env, product_id, ledgers)
+    }
+
     // Funding functions
-    pub fn contribute(env: Env, contributor: Address, product_id: u32, amount: u64) {
-        funding::contribute(env, contributor, product_id, amount)
+    pub fn contribute(
+        env: Env,
+        contributor: Address,
+        product_id: u32,
+        amount: u64,
+        refund_to: Option<Address>,
+        message: Option<String>,
+        token: Option<Address>,
+        tip: u64,
+    ) {
+        guard::lock(&env);
+        funding::contribute(
+            env.clone(),
+            contributor,
+            product_id,
+            amount,
+            refund_to,
+            message,
+            token,
+            tip,
+        );
+        guard::unlock(&env);
+    }
+
+    pub fn withdraw_contribution(env: Env, contributor: Address, product_id: u32) {
+        guard::lock(&env);
+        funding::withdraw_contribution(env.clone(), contributor, product_id);
+        guard::unlock(&env);
+    }
+
+    pub fn distribute_funds(env: Env, product_id: u32) -> DistributionReport {
+        guard::lock(&env);
+        let report = funding::distribute_funds(env.clone(), product_id);
+        guard::unlock(&env);
+        report
+    }
+
+    pub fn get_distribution_report(env: Env, product_id: u32) -> Option<DistributionReport> {
+        funding::get_distribution_report(env, product_id)
+    }
+
+    pub fn can_distribute(env: Env, product_id: u32) -> bool {
+        funding::can_distribute(env, product_id)
     }
 
-    pub fn distribute_funds(env: Env, product_id: u32) {
-        funding::distribute_funds(env, product_id)
+    pub fn withdraw_creator_funds(env: Env, creator: Address, product_id: u32, amount: u64) {
+        guard::lock(&env);
+        funding::withdraw_creator_funds(env.clone(), creator, product_id, amount);
+        guard::unlock(&env);
     }
 
     pub fn refund_contributors(env: Env, product_id: u32) {
-        funding::refund_contributors(env, product_id)
+        guard::lock(&env);
+        funding::refund_contributors(env.clone(), product_id);
+        guard::unlock(&env);
+    }
+
+    pub fn get_total_refundable(env: Env, product_id: u32) -> u64 {
+        funding::get_total_refundable(env, product_id)
+    }
+
+    pub fn get_fund_status(env: Env, product_id: u32) -> (u64, u64) {
+        funding::get_fund_status(env, product_id)
+    }
+
+    pub fn get_expected_balance(env: Env, token: Address) -> i128 {
+        funding::get_expected_balance(env, token)
+    }
+
+    pub fn get_refundable_amount(
+        env: Env,
+        product_id: u32,
+        contributor: Address,
+    ) -> Vec<(Address, u64)> {
+        funding::get_refundable_amount(env, product_id, contributor)
+    }
+
+    pub fn sweep_expired(env: Env, product_ids: Vec<u32>) {
+        guard::lock(&env);
+        funding::sweep_expired(env.clone(), product_ids);
+        guard::unlock(&env);
+    }
+
+    pub fn finalize_all(env: Env, product_ids: Vec<u32>) -> Vec<(u32, ProductStatus)> {
+        guard::lock(&env);
+        let statuses = funding::finalize_all(env.clone(), product_ids);
+        guard::unlock(&env);
+        statuses
+    }
+
+    pub fn refund_batch(env: Env, product_id: u32, start: u32, count: u32) {
+        guard::lock(&env);
+        funding::refund_batch(env.clone(), product_id, start, count);
+        guard::unlock(&env);
+    }
+
+    pub fn release_milestone_funds(env: Env, product_id: u32, milestone_id: u32) {
+        guard::lock(&env);
+        funding::release_milestone_funds(env.clone(), product_id, milestone_id);
+        guard::unlock(&env);
+    }
+
+    pub fn refund_remaining(env: Env, product_id: u32) {
+        guard::lock(&env);
+        funding::refund_remaining(env.clone(), product_id);
+        guard::unlock(&env);
+    }
+
+    pub fn trigger_milestone_refund(env: Env, product_id: u32, milestone_id: u32) {
+        guard::lock(&env);
+        funding::trigger_milestone_refund(env.clone(), product_id, milestone_id);
+        guard::unlock(&env);
+    }
+
+    pub fn cancel_product(env: Env, creator: Address, product_id: u32) {
+        guard::lock(&env);
+        funding::cancel_product(env.clone(), creator, product_id);
+        guard::unlock(&env);
+    }
+
+    pub fn reclaim_unwithdrawn(env: Env, contributor: Address, product_id: u32) {
+        guard::lock(&env);
+        funding::reclaim_unwithdrawn(env.clone(), contributor, product_id);
+        guard::unlock(&env);
     }
 
     // Reward functions
@@ -70,23 +449,242 @@ impl CrowdfundingCollective {
     }
 
     // Tracking functions
-    pub fn update_milestone(env: Env, creator: Address, product_id: u32, milestone_id: u32) {
-        tracking::update_milestone(env, creator, product_id, milestone_id)
+    pub fn update_milestone(
+        env: Env,
+        creator: Address,
+        product_id: u32,
+        milestone_id: u32,
+        completion_note: String,
+    ) {
+        tracking::update_milestone(env, creator, product_id, milestone_id, completion_note)
+    }
+
+    pub fn remove_milestone(env: Env, creator: Address, product_id: u32, milestone_id: u32) {
+        tracking::remove_milestone(env, creator, product_id, milestone_id)
+    }
+
+    pub fn reorder_milestones(env: Env, creator: Address, product_id: u32, new_order: Vec<u32>) {
+        tracking::reorder_milestones(env, creator, product_id, new_order)
+    }
+
+    pub fn set_milestone_progress(
+        env: Env,
+        creator: Address,
+        product_id: u32,
+        milestone_id: u32,
+        progress: u32,
+    ) {
+        tracking::set_milestone_progress(env, creator, product_id, milestone_id, progress)
+    }
+
+    pub fn raise_dispute(env: Env, contributor: Address, product_id: u32, reason: String) {
+        dispute::raise_dispute(env, contributor, product_id, reason)
+    }
+
+    pub fn resolve_dispute(env: Env, admin: Address, product_id: u32, refund: bool) {
+        guard::lock(&env);
+        dispute::resolve_dispute(env.clone(), admin, product_id, refund);
+        guard::unlock(&env);
     }
 
     pub fn get_product(env: Env, product_id: u32) -> Product {
         product::get_product(env, product_id)
     }
 
+    pub fn get_product_or_none(env: Env, product_id: u32) -> Option<Product> {
+        product::get_product_or_none(env, product_id)
+    }
+
+    pub fn get_products_by_ids(env: Env, ids: Vec<u32>) -> Vec<Product> {
+        product::get_products_by_ids(env, ids)
+    }
+
+    pub fn get_product_token_info(env: Env, product_id: u32) -> (Address, u32) {
+        product::get_product_token_info(env, product_id)
+    }
+
+    pub fn get_product_summary(env: Env, product_id: u32) -> ProductSummary {
+        tracking::get_product_summary(env, product_id)
+    }
+
+    pub fn get_average_contribution(env: Env, product_id: u32) -> u64 {
+        tracking::get_average_contribution(env, product_id)
+    }
+
+    pub fn get_status_history(env: Env, product_id: u32) -> Vec<(ProductStatus, u64)> {
+        product::get_status_history(env, product_id)
+    }
+
+    pub fn is_goal_reached(env: Env, product_id: u32) -> bool {
+        product::is_goal_reached(env, product_id)
+    }
+
+    pub fn get_remaining_capacity(env: Env, product_id: u32) -> u64 {
+        product::get_remaining_capacity(env, product_id)
+    }
+
+    pub fn is_deadline_passed(env: Env, product_id: u32) -> bool {
+        product::is_deadline_passed(env, product_id)
+    }
+
+    pub fn get_campaign_duration(env: Env, product_id: u32) -> u64 {
+        product::get_campaign_duration(env, product_id)
+    }
+
+    pub fn get_elapsed_time(env: Env, product_id: u32) -> u64 {
+        product::get_elapsed_time(env, product_id)
+    }
+
+    pub fn get_funding_velocity(env: Env, product_id: u32) -> u64 {
+        product::get_funding_velocity(env, product_id)
+    }
+
+    pub fn get_projected_completion(env: Env, product_id: u32) -> Option<u64> {
+        product::get_projected_completion(env, product_id)
+    }
+
+    pub fn get_products_by_category(
+        env: Env,
+        category: Symbol,
+        start_id: u32,
+        limit: u32,
+    ) -> Vec<Product> {
+        product::get_products_by_category(env, category, start_id, limit)
+    }
+
+    pub fn get_creator_products(env: Env, creator: Address) -> Vec<u32> {
+        product::get_creator_products(env, creator)
+    }
+
+    pub fn get_creator_action_items(env: Env, creator: Address) -> Vec<(u32, Symbol)> {
+        product::get_creator_action_items(env, creator)
+    }
+
+    pub fn get_platform_stats(env: Env) -> PlatformStats {
+        stats::get_platform_stats(env)
+    }
+
+    pub fn get_active_count(env: Env) -> u32 {
+        stats::get_active_count(env)
+    }
+
+    pub fn get_closed_count(env: Env) -> u32 {
+        stats::get_closed_count(env)
+    }
+
+    pub fn get_products_ending_soon(
+        env: Env,
+        within_seconds: u64,
+        start_id: u32,
+        limit: u32,
+    ) -> Vec<Product> {
+        product::get_products_ending_soon(env, within_seconds, start_id, limit)
+    }
+
+    pub fn get_stalled_funded(env: Env, stalled_after: u64, start_id: u32, limit: u32) -> Vec<u32> {
+        product::get_stalled_funded(env, stalled_after, start_id, limit)
+    }
+
     pub fn get_contributions(env: Env, product_id: u32) -> Vec<Contribution> {
         tracking::get_contributions(env, product_id)
     }
 
+    pub fn get_all_contributors(env: Env, product_id: u32) -> Vec<Address> {
+        tracking::get_all_contributors(env, product_id)
+    }
+
+    pub fn get_contribution(
+        env: Env,
+        product_id: u32,
+        contributor: Address,
+    ) -> Option<Contribution> {
+        tracking::get_contribution(env, product_id, contributor)
+    }
+
+    pub fn is_contributor(env: Env, product_id: u32, addr: Address) -> bool {
+        tracking::is_contributor(env, product_id, addr)
+    }
+
+    pub fn get_contributions_paged(
+        env: Env,
+        product_id: u32,
+        start: u32,
+        limit: u32,
+    ) -> Vec<Contribution> {
+        tracking::get_contributions_paged(env, product_id, start, limit)
+    }
+
+    pub fn get_contributor_history(env: Env, contributor: Address) -> Vec<u32> {
+        funding::get_contributor_history(env, contributor)
+    }
+
+    pub fn get_top_contributors(env: Env, product_id: u32, n: u32) -> Vec<Contribution> {
+        tracking::get_top_contributors(env, product_id, n)
+    }
+
+    pub fn get_contributions_by_amount(
+        env: Env,
+        product_id: u32,
+        min: u64,
+        max: u64,
+    ) -> Vec<Contribution> {
+        tracking::get_contributions_by_amount(env, product_id, min, max)
+    }
+
     pub fn get_milestones(env: Env, product_id: u32) -> Vec<Milestone> {
         tracking::get_milestones(env, product_id)
     }
 
+    pub fn get_completed_milestone_count(env: Env, product_id: u32) -> u32 {
+        tracking::get_completed_milestone_count(env, product_id)
+    }
+
+    pub fn get_milestone_allocation(env: Env, product_id: u32, milestone_id: u32) -> u64 {
+        tracking::get_milestone_allocation(env, product_id, milestone_id)
+    }
+
+    pub fn get_milestone_time_remaining(env: Env, product_id: u32, milestone_id: u32) -> u64 {
+        tracking::get_milestone_time_remaining(env, product_id, milestone_id)
+    }
+
+    pub fn mark_reward_fulfilled(
+        env: Env,
+        creator: Address,
+        product_id: u32,
+        contributor: Address,
+    ) {
+        rewards::mark_reward_fulfilled(env, creator, product_id, contributor)
+    }
+
+    pub fn is_reward_fulfilled(env: Env, product_id: u32, contributor: Address) -> bool {
+        rewards::is_reward_fulfilled(env, product_id, contributor)
+    }
+
+    pub fn preview_reward_tier(
+        env: Env,
+        product_id: u32,
+        contributor: Address,
+    ) -> Option<RewardTier> {
+        rewards::preview_reward_tier(env, product_id, contributor)
+    }
+
+    pub fn get_dispute_count(env: Env, product_id: u32) -> u32 {
+        dispute::get_dispute_count(env, product_id)
+    }
+
+    pub fn get_disputes(env: Env, product_id: u32) -> Vec<Dispute> {
+        dispute::get_disputes(env, product_id)
+    }
+
     pub fn get_reward_tiers(env: Env, product_id: u32) -> Vec<RewardTier> {
         rewards::get_reward_tiers(env, product_id)
     }
+
+    pub fn get_tier_breakdown(env: Env, product_id: u32) -> Vec<(u32, u64, u32)> {
+        rewards::get_tier_breakdown(env, product_id)
+    }
+
+    pub fn get_reward_tiers_sorted(env: Env, product_id: u32) -> Vec<RewardTier> {
+        rewards::get_reward_tiers_sorted(env, product_id)
+    }
 }
@@ -1,24 +1,143 @@
+use crate::admin::ensure_creator_allowed;
+use crate::funding::{finalizes_as_funded, TOKEN_WEIGHT_UNIT};
+use crate::rewards::validate_reward_tiers;
+use crate::stats;
+use crate::tracking::{get_milestones, validate_milestone_weights};
 use crate::types::*;
-use soroban_sdk::{Address, Env, String, Vec};
+use soroban_sdk::{token, Address, Env, String, Symbol, Vec};
+
+// Ledgers close roughly every 5 seconds, so one day is about 17,280 ledgers.
+// Product data is bumped back to ~120 days whenever its remaining TTL drops below ~30 days,
+// which keeps multi-month campaigns alive as long as they see activity.
+pub const PRODUCT_TTL_THRESHOLD: u32 = 17_280 * 30;
+pub const PRODUCT_TTL_EXTEND_TO: u32 = 17_280 * 120;
+
+// Milestone cap used until the admin configures one, keeps milestone scans cheap
+pub const DEFAULT_MAX_MILESTONES: u32 = 20;
+
+// Reward tier cap used until the admin configures one, keeps claim_reward's tier scan cheap
+pub const DEFAULT_MAX_REWARD_TIERS: u32 = 10;
+
+// Upper bound on products returned by paginated queries
+pub const MAX_PAGE_SIZE: u32 = 50;
+
+// Longest metadata URI a creator can attach to a product, in bytes
+pub const MAX_METADATA_URI_LENGTH: u32 = 256;
+
+// Status transitions kept per product; reopening can cycle Failed and Active, so the oldest drop off
+pub const MAX_STATUS_HISTORY: u32 = 20;
 
 pub fn create_product(
     env: Env,
     creator: Address,
     name: String,
     description: String,
+    metadata_uri: Option<String>,
     funding_goal: u64,
     deadline: u64, // Changed from &u64
     reward_tiers: Vec<RewardTier>,
     milestones: Vec<Milestone>,
+    category: Symbol,
+    funding_model: Option<FundingModel>,
+) -> u32 {
+    let token: Address = env
+        .storage()
+        .instance()
+        .get(&DataKey::Token)
+        .unwrap_or_else(|| panic!("Token not configured"));
+    create_with_token(
+        env,
+        creator,
+        name,
+        description,
+        metadata_uri,
+        funding_goal,
+        deadline,
+        reward_tiers,
+        milestones,
+        category,
+        funding_model,
+        token,
+    )
+}
+
+// Same as create_product, but contributions are made in the native asset (XLM) through its SAC
+pub fn create_native_product(
+    env: Env,
+    creator: Address,
+    name: String,
+    description: String,
+    metadata_uri: Option<String>,
+    funding_goal: u64,
+    deadline: u64,
+    reward_tiers: Vec<RewardTier>,
+    milestones: Vec<Milestone>,
+    category: Symbol,
+    funding_model: Option<FundingModel>,
+) -> u32 {
+    let token: Address = env
+        .storage()
+        .instance()
+        .get(&DataKey::NativeToken)
+        .unwrap_or_else(|| panic!("Native token not configured"));
+    create_with_token(
+        env,
+        creator,
+        name,
+        description,
+        metadata_uri,
+        funding_goal,
+        deadline,
+        reward_tiers,
+        milestones,
+        category,
+        funding_model,
+        token,
+    )
+}
+
+fn create_with_token(
+    env: Env,
+    creator: Address,
+    name: String,
+    description: String,
+    metadata_uri: Option<String>,
+    funding_goal: u64,
+    deadline: u64,
+    reward_tiers: Vec<RewardTier>,
+    milestones: Vec<Milestone>,
+    category: Symbol,
+    funding_model: Option<FundingModel>,
+    token: Address,
 ) -> u32 {
     creator.require_auth();
+    ensure_creator_allowed(&env, &creator);
 
     // Validate inputs
-    if funding_goal == 0 {
-        panic!("Funding goal must be greater than zero");
+    validate_funding_goal(&env, funding_goal);
+    validate_deadline(&env, deadline);
+    let metadata_uri = metadata_uri.unwrap_or_else(|| String::from_str(&env, ""));
+    validate_metadata_uri(&metadata_uri);
+    let max_milestones: u32 = env
+        .storage()
+        .instance()
+        .get(&DataKey::MaxMilestones)
+        .unwrap_or(DEFAULT_MAX_MILESTONES);
+    if milestones.len() > max_milestones {
+        panic!("Too many milestones");
     }
-    if deadline <= env.ledger().timestamp() {
-        panic!("Deadline must be in the future");
+    validate_milestone_weights(&milestones);
+    let max_reward_tiers: u32 = env
+        .storage()
+        .instance()
+        .get(&DataKey::MaxRewardTiers)
+        .unwrap_or(DEFAULT_MAX_REWARD_TIERS);
+    if reward_tiers.len() > max_reward_tiers {
+        panic!("Too many reward tiers");
+    }
+    validate_reward_tiers(&reward_tiers);
+    if category == Symbol::new(&env, "") {
+        panic!("Category must not be empty");
     }
 
     // Get next product ID
@@ -27,47 +146,939 @@ pub fn create_product(
     // Create product
     let product = Product {
         id: product_id,
-        creator,
+        creator: creator.clone(),
         name,
         description,
+        metadata_uri,
         funding_goal,
         deadline,
         status: ProductStatus::Active,
         total_funded: 0,
+        enforce_milestone_dates: false,
+        released_funds: 0,
+        category: category.clone(),
+        token: token.clone(),
+        decimals: token::Client::new(&env, &token).decimals(),
+        contribution_cooldown: 0,
+        dispute_count: 0,
+        disputed_amount: 0,
+        reward_strategy: RewardStrategy::Highest,
+        funding_model: funding_model.unwrap_or_else(|| default_funding_model(&env)),
+        beneficiary: creator,
+        created_at: env.ledger().timestamp(),
+        accept_partial: false,
+        accepted_tokens: Vec::from_array(&env, [(token, TOKEN_WEIGHT_UNIT)]),
+        anonymous: false,
+        reward_claim_period: 0,
+        reward_claim_deadline: 0,
+        auto_extend: false,
+        auto_extend_window: 0,
+        auto_extend_by: 0,
+        auto_extensions: 0,
+        max_contributors: 0,
+        allow_self_contribution: true,
+        paused: false,
     };
 
     // Store product
+    stats::record_product_created(&env);
     env.storage()
-        .instance()
+        .persistent()
         .set(&DataKey::Products(product_id), &product);
+    record_status_history(&env, product_id, ProductStatus::Active);
 
     // Store reward tiers and milestones
-    env.storage()
-        .instance()
-        .set(&DataKey::Rewards(product_id), &reward_tiers);
-    env.storage()
-        .instance()
-        .set(&DataKey::Milestones(product_id), &milestones);
+    env.storage().persistent().set(
+        &DataKey::Rewards(product_id),
+        &fresh_reward_tiers(&env, &reward_tiers),
+    );
+    env.storage().persistent().set(
+        &DataKey::Milestones(product_id),
+        &fresh_milestones(&env, &milestones),
+    );
 
     // Initialize contributions
     let contributions: Vec<Contribution> = Vec::new(&env);
     env.storage()
-        .instance()
+        .persistent()
         .set(&DataKey::Contributions(product_id), &contributions);
     env.storage()
-        .instance()
+        .persistent()
         .set(&DataKey::ContributionsTotal(product_id), &0u64);
 
+    // Index the product under its category
+    let category_key = DataKey::CategoryProducts(category);
+    let mut category_products: Vec<u32> = env
+        .storage()
+        .persistent()
+        .get(&category_key)
+        .unwrap_or_else(|| Vec::new(&env));
+    category_products.push_back(product_id);
+    env.storage()
+        .persistent()
+        .set(&category_key, &category_products);
+    env.storage().persistent().extend_ttl(
+        &category_key,
+        PRODUCT_TTL_THRESHOLD,
+        PRODUCT_TTL_EXTEND_TO,
+    );
+
+    // And under its creator
+    let creator_key = DataKey::CreatorProducts(product.creator.clone());
+    let mut creator_products = get_creator_products(env.clone(), product.creator.clone());
+    creator_products.push_back(product_id);
+    env.storage()
+        .persistent()
+        .set(&creator_key, &creator_products);
+    env.storage().persistent().extend_ttl(
+        &creator_key,
+        PRODUCT_TTL_THRESHOLD,
+        PRODUCT_TTL_EXTEND_TO,
+    );
+
+    bump_product_ttl(&env, product_id);
+
+    // The creator is a topic so indexers can filter a creator's campaigns without scanning
+    env.events().publish(
+        (
+            Symbol::new(&env, "product_created"),
+            product_id,
+            product.creator,
+        ),
+        (product.funding_goal, product.deadline),
+    );
+
     product_id
 }
 
 pub fn get_product(env: Env, product_id: u32) -> Product {
     env.storage()
-        .instance()
+        .persistent()
         .get(&DataKey::Products(product_id))
         .unwrap_or_else(|| panic!("Product not found"))
 }
 
+// Like get_product, but returns None for unknown IDs instead of panicking.
+// (A `try_get_product` name would clash with the generated client method.)
+pub fn get_product_or_none(env: Env, product_id: u32) -> Option<Product> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Products(product_id))
+}
+
+// The product token with its decimals, so UIs can format the raw amounts
+pub fn get_product_token_info(env: Env, product_id: u32) -> (Address, u32) {
+    let product = get_product(env, product_id);
+    (product.token, product.decimals)
+}
+
+// Products for the given IDs in the order asked for, skipping unknown IDs; at most
+// MAX_PAGE_SIZE IDs per call
+pub fn get_products_by_ids(env: Env, ids: Vec<u32>) -> Vec<Product> {
+    if ids.len() > MAX_PAGE_SIZE {
+        panic!("Too many product IDs");
+    }
+    let mut products = Vec::new(&env);
+    for product_id in ids.iter() {
+        if let Some(product) = get_product_or_none(env.clone(), product_id) {
+            products.push_back(product);
+        }
+    }
+    products
+}
+
+// Statuses the product has held with the time each began, oldest first
+pub fn get_status_history(env: Env, product_id: u32) -> Vec<(ProductStatus, u64)> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::StatusHistory(product_id))
+        .unwrap_or_else(|| Vec::new(&env))
+}
+
+pub fn is_goal_reached(env: Env, product_id: u32) -> bool {
+    let product = get_product(env, product_id);
+    product.total_funded >= product.funding_goal
+}
+
+// Largest contribution the goal still has room for, so front-ends can pre-fill it. Only
+// all-or-nothing campaigns are capped; the other models report u64::MAX while they are open.
+// 0 once the campaign stops taking contributions.
+pub fn get_remaining_capacity(env: Env, product_id: u32) -> u64 {
+    let product = get_product(env.clone(), product_id);
+    if product.status != ProductStatus::Active || env.ledger().timestamp() > product.deadline {
+        return 0;
+    }
+    if product.funding_model != FundingModel::AllOrNothing {
+        return u64::MAX;
+    }
+    product.funding_goal.saturating_sub(product.total_funded)
+}
+
+// True from the deadline second itself onwards
+pub fn is_deadline_passed(env: Env, product_id: u32) -> bool {
+    let product = get_product(env.clone(), product_id);
+    env.ledger().timestamp() >= product.deadline
+}
+
+// Seconds from creation to the deadline
+pub fn get_campaign_duration(env: Env, product_id: u32) -> u64 {
+    let product = get_product(env, product_id);
+    product.deadline - product.created_at
+}
+
+// Seconds since creation, capped at the campaign duration once the deadline passes
+pub fn get_elapsed_time(env: Env, product_id: u32) -> u64 {
+    let product = get_product(env.clone(), product_id);
+    env.ledger().timestamp().min(product.deadline) - product.created_at
+}
+
+// Average amount raised per second since creation, rounded down; 0 before any time has passed
+pub fn get_funding_velocity(env: Env, product_id: u32) -> u64 {
+    let product = get_product(env.clone(), product_id);
+    let elapsed = get_elapsed_time(env, product_id);
+    if elapsed == 0 {
+        return 0;
+    }
+    product.total_funded / elapsed
+}
+
+// Seconds until the goal is reached if funding keeps its current velocity:
+// ceil((funding_goal - total_funded) / velocity). None once the goal is met or at zero velocity.
+pub fn get_projected_completion(env: Env, product_id: u32) -> Option<u64> {
+    let product = get_product(env.clone(), product_id);
+    let velocity = get_funding_velocity(env, product_id);
+    if velocity == 0 || product.total_funded >= product.funding_goal {
+        return None;
+    }
+    Some((product.funding_goal - product.total_funded).div_ceil(velocity))
+}
+
+// Products in a category with ID >= start_id, in creation order
+pub fn get_products_by_category(
+    env: Env,
+    category: Symbol,
+    start_id: u32,
+    limit: u32,
+) -> Vec<Product> {
+    let product_ids: Vec<u32> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::CategoryProducts(category))
+        .unwrap_or_else(|| Vec::new(&env));
+
+    let limit = limit.min(MAX_PAGE_SIZE);
+    let mut products = Vec::new(&env);
+    for product_id in product_ids.iter() {
+        if products.len() >= limit {
+            break;
+        }
+        if product_id >= start_id {
+            products.push_back(get_product(env.clone(), product_id));
+        }
+    }
+    products
+}
+
+// Product IDs created by `creator`, in creation order
+pub fn get_creator_products(env: Env, creator: Address) -> Vec<u32> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::CreatorProducts(creator))
+        .unwrap_or_else(|| Vec::new(&env))
+}
+
+// The creator's products that are waiting on something, one entry per pending action:
+// "finalize" for an expired Active campaign, "milestone" for a Funded product with milestones
+// still open, and "dispute" while backers have disputes open
+pub fn get_creator_action_items(env: Env, creator: Address) -> Vec<(u32, Symbol)> {
+    let now = env.ledger().timestamp();
+    let mut items = Vec::new(&env);
+    for product_id in get_creator_products(env.clone(), creator).iter() {
+        let product = get_product(env.clone(), product_id);
+        if product.status == ProductStatus::Active && now > product.deadline {
+            items.push_back((product_id, Symbol::new(&env, "finalize")));
+        }
+        if product.status == ProductStatus::Funded
+            && get_milestones(env.clone(), product_id)
+                .iter()
+                .any(|milestone| !milestone.completed)
+        {
+            items.push_back((product_id, Symbol::new(&env, "milestone")));
+        }
+        if product.disputed_amount > 0 {
+            items.push_back((product_id, Symbol::new(&env, "dispute")));
+        }
+    }
+    items
+}
+
+// Active products whose deadline is still ahead but no more than `within_seconds` away, among the
+// `limit` product IDs (capped at MAX_PAGE_SIZE) starting at `start_id`. Callers page through the
+// platform by advancing `start_id` by `limit`.
+pub fn get_products_ending_soon(
+    env: Env,
+    within_seconds: u64,
+    start_id: u32,
+    limit: u32,
+) -> Vec<Product> {
+    let now = env.ledger().timestamp();
+    let mut products = Vec::new(&env);
+    for product_id in id_window(&env, start_id, limit) {
+        let Some(product) = get_product_or_none(env.clone(), product_id) else {
+            continue;
+        };
+        let remaining = product.deadline.saturating_sub(now);
+        if product.status == ProductStatus::Active && remaining > 0 && remaining <= within_seconds {
+            products.push_back(product);
+        }
+    }
+    products
+}
+
+// The product IDs a paginated scan looks at: `limit` of them (capped at MAX_PAGE_SIZE) from
+// `start_id`, so a single call stays within read budgets however many products exist
+fn id_window(env: &Env, start_id: u32, limit: u32) -> core::ops::Range<u32> {
+    let next_id: u32 = env
+        .storage()
+        .instance()
+        .get(&DataKey::NextProductId)
+        .unwrap_or(1);
+    let start = start_id.max(1);
+    start..start.saturating_add(limit.min(MAX_PAGE_SIZE)).min(next_id)
+}
+
+// Funded products with an incomplete milestone whose deadline passed more than `stalled_after`
+// seconds ago, among the `limit` product IDs starting at `start_id`; for keepers chasing pending
+// milestone work
+pub fn get_stalled_funded(env: Env, stalled_after: u64, start_id: u32, limit: u32) -> Vec<u32> {
+    let now = env.ledger().timestamp();
+    let mut product_ids = Vec::new(&env);
+    for product_id in id_window(&env, start_id, limit) {
+        let Some(product) = get_product_or_none(env.clone(), product_id) else {
+            continue;
+        };
+        if product.status == ProductStatus::Funded
+            && now > product.deadline.saturating_add(stalled_after)
+            && get_milestones(env.clone(), product_id)
+                .iter()
+                .any(|milestone| !milestone.completed)
+        {
+            product_ids.push_back(product_id);
+        }
+    }
+    product_ids
+}
+
+pub fn update_product_details(
+    env: Env,
+    creator: Address,
+    product_id: u32,
+    name: String,
+    description: String,
+    metadata_uri: String,
+) {
+    creator.require_auth();
+
+    let mut product = get_product(env.clone(), product_id);
+    if product.creator != creator {
+        panic!("Only the creator can edit");
+    }
+    if product.status != ProductStatus::Active {
+        panic!("Cannot edit a funded product");
+    }
+    // Empty clears the URI
+    validate_metadata_uri(&metadata_uri);
+
+    product.name = name;
+    product.description = description;
+    product.metadata_uri = metadata_uri;
+    env.storage()
+        .persistent()
+        .set(&DataKey::Products(product_id), &product);
+}
+
+pub fn update_funding_goal(env: Env, creator: Address, product_id: u32, new_goal: u64) {
+    creator.require_auth();
+
+    let mut product = get_product(env.clone(), product_id);
+    if product.creator != creator {
+        panic!("Only the creator can edit");
+    }
+    if product.status != ProductStatus::Active {
+        panic!("Product is not active");
+    }
+    if product.total_funded > 0 {
+        panic!("Cannot change goal after contributions");
+    }
+    validate_funding_goal(&env, new_goal);
+
+    product.funding_goal = new_goal;
+    env.storage()
+        .persistent()
+        .set(&DataKey::Products(product_id), &product);
+}
+
+pub fn set_milestone_date_enforcement(env: Env, creator: Address, product_id: u32, enforce: bool) {
+    creator.require_auth();
+
+    let mut product = get_product(env.clone(), product_id);
+    if product.creator != creator {
+        panic!("Only the creator can update settings");
+    }
+    if product.status != ProductStatus::Active {
+        panic!("Product is not active");
+    }
+
+    product.enforce_milestone_dates = enforce;
+    env.storage()
+        .persistent()
+        .set(&DataKey::Products(product_id), &product);
+}
+
+// Throttle repeat contributions from the same backer; 0 disables the cooldown
+pub fn set_contribution_cooldown(env: Env, creator: Address, product_id: u32, seconds: u64) {
+    creator.require_auth();
+
+    let mut product = get_product(env.clone(), product_id);
+    if product.creator != creator {
+        panic!("Only the creator can update settings");
+    }
+    if product.status != ProductStatus::Active {
+        panic!("Product is not active");
+    }
+
+    product.contribution_cooldown = seconds;
+    env.storage()
+        .persistent()
+        .set(&DataKey::Products(product_id), &product);
+}
+
+// Caps distinct backers to keep the contributions list bounded; 0 removes the cap. Lowering it
+// below the current count only turns away new backers.
+pub fn set_max_contributors(env: Env, creator: Address, product_id: u32, max: u32) {
+    creator.require_auth();
+
+    let mut product = get_product(env.clone(), product_id);
+    if product.creator != creator {
+        panic!("Only the creator can update settings");
+    }
+    if product.status != ProductStatus::Active {
+        panic!("Product is not active");
+    }
+
+    product.max_contributors = max;
+    env.storage()
+        .persistent()
+        .set(&DataKey::Products(product_id), &product);
+}
+
+// Creators can halt contributions to their own campaign for a while; the deadline keeps running
+pub fn pause_product(env: Env, creator: Address, product_id: u32) {
+    set_paused(&env, &creator, product_id, true);
+    env.events()
+        .publish((Symbol::new(&env, "ProductPaused"), product_id), creator);
+}
+
+pub fn resume_product(env: Env, creator: Address, product_id: u32) {
+    set_paused(&env, &creator, product_id, false);
+    env.events()
+        .publish((Symbol::new(&env, "ProductResumed"), product_id), creator);
+}
+
+fn set_paused(env: &Env, creator: &Address, product_id: u32, paused: bool) {
+    creator.require_auth();
+
+    let mut product = get_product(env.clone(), product_id);
+    if product.creator != *creator {
+        panic!("Only the creator can update settings");
+    }
+    if product.status != ProductStatus::Active {
+        panic!("Product is not active");
+    }
+
+    product.paused = paused;
+    env.storage()
+        .persistent()
+        .set(&DataKey::Products(product_id), &product);
+}
+
+// Lets a creator rule out self-funding; the creator's earlier contributions are kept
+pub fn set_allow_self_contribution(env: Env, creator: Address, product_id: u32, allowed: bool) {
+    creator.require_auth();
+
+    let mut product = get_product(env.clone(), product_id);
+    if product.creator != creator {
+        panic!("Only the creator can update settings");
+    }
+    if product.status != ProductStatus::Active {
+        panic!("Product is not active");
+    }
+
+    product.allow_self_contribution = allowed;
+    env.storage()
+        .persistent()
+        .set(&DataKey::Products(product_id), &product);
+}
+
+pub fn set_reward_strategy(env: Env, creator: Address, product_id: u32, strategy: RewardStrategy) {
+    creator.require_auth();
+
+    let mut product = get_product(env.clone(), product_id);
+    if product.creator != creator {
+        panic!("Only the creator can update settings");
+    }
+    if product.status != ProductStatus::Active {
+        panic!("Product is not active");
+    }
+
+    product.reward_strategy = strategy;
+    env.storage()
+        .persistent()
+        .set(&DataKey::Products(product_id), &product);
+}
+
+// How long backers have to claim rewards once the product completes; the clock starts at
+// distribution, so it is fixed while the campaign is still open
+pub fn set_reward_claim_period(env: Env, creator: Address, product_id: u32, seconds: u64) {
+    creator.require_auth();
+
+    let mut product = get_product(env.clone(), product_id);
+    if product.creator != creator {
+        panic!("Only the creator can update settings");
+    }
+    if product.status != ProductStatus::Active {
+        panic!("Product is not active");
+    }
+
+    product.reward_claim_period = seconds;
+    env.storage()
+        .persistent()
+        .set(&DataKey::Products(product_id), &product);
+}
+
+// Anti-sniping: a campaign that expires within reach of its goal runs for `extend_by` more
+// seconds instead of failing, if it is resolved no later than `window` seconds after the deadline
+pub fn set_auto_extend(
+    env: Env,
+    creator: Address,
+    product_id: u32,
+    enabled: bool,
+    window: u64,
+    extend_by: u64,
+) {
+    creator.require_auth();
+
+    let mut product = get_product(env.clone(), product_id);
+    if product.creator != creator {
+        panic!("Only the creator can update settings");
+    }
+    if product.status != ProductStatus::Active {
+        panic!("Product is not active");
+    }
+    if enabled && extend_by == 0 {
+        panic!("Extension must be greater than zero");
+    }
+
+    product.auto_extend = enabled;
+    product.auto_extend_window = window;
+    product.auto_extend_by = extend_by;
+    env.storage()
+        .persistent()
+        .set(&DataKey::Products(product_id), &product);
+}
+
+// Lets all-or-nothing campaigns accept the part of a contribution that fits under the goal
+pub fn set_accept_partial(env: Env, creator: Address, product_id: u32, enabled: bool) {
+    creator.require_auth();
+
+    let mut product = get_product(env.clone(), product_id);
+    if product.creator != creator {
+        panic!("Only the creator can update settings");
+    }
+    if product.status != ProductStatus::Active {
+        panic!("Product is not active");
+    }
+
+    product.accept_partial = enabled;
+    env.storage()
+        .persistent()
+        .set(&DataKey::Products(product_id), &product);
+}
+
+// Only changes what the contribution listings show, so it can be toggled at any stage
+pub fn set_anonymous(env: Env, creator: Address, product_id: u32, enabled: bool) {
+    creator.require_auth();
+
+    let mut product = get_product(env.clone(), product_id);
+    if product.creator != creator {
+        panic!("Only the creator can update settings");
+    }
+
+    product.anonymous = enabled;
+    env.storage()
+        .persistent()
+        .set(&DataKey::Products(product_id), &product);
+}
+
+// Tokens contributions can be made in, each credited toward the goal at `weight / 1_000` of its
+// amount. Replaces the default of the product token at full weight, before any contributions.
+pub fn set_accepted_tokens(
+    env: Env,
+    creator: Address,
+    product_id: u32,
+    tokens: Vec<(Address, u64)>,
+) {
+    creator.require_auth();
+
+    let mut product = get_product(env.clone(), product_id);
+    if product.creator != creator {
+        panic!("Only the creator can update settings");
+    }
+    if product.status != ProductStatus::Active {
+        panic!("Product is not active");
+    }
+    if product.total_funded > 0 {
+        panic!("Cannot change tokens after contributions");
+    }
+    if tokens.is_empty() {
+        panic!("At least one token must be accepted");
+    }
+    for (_, weight) in tokens.iter() {
+        if weight == 0 {
+            panic!("Token weight must be greater than zero");
+        }
+    }
+
+    product.accepted_tokens = tokens;
+    env.storage()
+        .persistent()
+        .set(&DataKey::Products(product_id), &product);
+}
+
+// Routes payouts to another address, e.g. an organization's treasury
+pub fn update_beneficiary(env: Env, creator: Address, product_id: u32, new_beneficiary: Address) {
+    creator.require_auth();
+
+    let mut product = get_product(env.clone(), product_id);
+    if product.creator != creator {
+        panic!("Only the creator can update settings");
+    }
+    if product.status != ProductStatus::Active {
+        panic!("Product is not active");
+    }
+
+    product.beneficiary = new_beneficiary;
+    env.storage()
+        .persistent()
+        .set(&DataKey::Products(product_id), &product);
+}
+
+// Hands a live campaign to another creator, who must pass the same verification check as a new
+// campaign would. A beneficiary that was still the old creator moves to the new one; a separately
+// set beneficiary is kept, and the new creator can change it while Active.
+pub fn transfer_product_ownership(
+    env: Env,
+    current_creator: Address,
+    product_id: u32,
+    new_creator: Address,
+) {
+    current_creator.require_auth();
+
+    let mut product = get_product(env.clone(), product_id);
+    if product.creator != current_creator {
+        panic!("Only the creator can transfer ownership");
+    }
+    if product.status != ProductStatus::Active && product.status != ProductStatus::Funded {
+        panic!("Product is closed");
+    }
+    if new_creator == current_creator {
+        panic!("Already the creator");
+    }
+    ensure_creator_allowed(&env, &new_creator);
+
+    // Payouts follow the new owner unless a separate beneficiary was set
+    if product.beneficiary == current_creator {
+        product.beneficiary = new_creator.clone();
+    }
+    product.creator = new_creator.clone();
+    env.storage()
+        .persistent()
+        .set(&DataKey::Products(product_id), &product);
+
+    // Move the product between creator indexes, keeping the new creator's in creation order
+    let mut previous = get_creator_products(env.clone(), current_creator.clone());
+    if let Some(i) = previous.first_index_of(product_id) {
+        previous.remove(i);
+    }
+    env.storage().persistent().set(
+        &DataKey::CreatorProducts(current_creator.clone()),
+        &previous,
+    );
+    let creator_key = DataKey::CreatorProducts(new_creator.clone());
+    let mut products = get_creator_products(env.clone(), new_creator.clone());
+    let position = products
+        .iter()
+        .position(|id| id > product_id)
+        .unwrap_or(products.len() as usize);
+    products.insert(position as u32, product_id);
+    env.storage().persistent().set(&creator_key, &products);
+    env.storage().persistent().extend_ttl(
+        &creator_key,
+        PRODUCT_TTL_THRESHOLD,
+        PRODUCT_TTL_EXTEND_TO,
+    );
+
+    env.events().publish(
+        (Symbol::new(&env, "OwnershipTransferred"), product_id),
+        (current_creator, new_creator),
+    );
+}
+
+// The funding model can only change before anyone has contributed
+pub fn set_funding_model(env: Env, creator: Address, product_id: u32, model: FundingModel) {
+    creator.require_auth();
+
+    let mut product = get_product(env.clone(), product_id);
+    if product.creator != creator {
+        panic!("Only the creator can update settings");
+    }
+    if product.status != ProductStatus::Active {
+        panic!("Product is not active");
+    }
+    if product.total_funded > 0 {
+        panic!("Cannot change funding model after contributions");
+    }
+
+    product.funding_model = model;
+    env.storage()
+        .persistent()
+        .set(&DataKey::Products(product_id), &product);
+}
+
+// Stop accepting contributions once the goal is met, without waiting for the deadline
+pub fn close_funding(env: Env, creator: Address, product_id: u32) {
+    creator.require_auth();
+
+    let mut product = get_product(env.clone(), product_id);
+    if product.creator != creator {
+        panic!("Only the creator can close funding");
+    }
+    if product.status != ProductStatus::Active {
+        panic!("Product is not active");
+    }
+    if product.total_funded < product.funding_goal {
+        panic!("Goal not reached");
+    }
+
+    set_status(&env, &mut product, ProductStatus::Funded);
+    env.storage()
+        .persistent()
+        .set(&DataKey::Products(product_id), &product);
+
+    env.events().publish(
+        (Symbol::new(&env, "FundingClosed"), product_id),
+        product.total_funded,
+    );
+}
+
+// Restart a failed campaign once every backer has been refunded
+pub fn reopen_product(env: Env, creator: Address, product_id: u32, new_deadline: u64) {
+    creator.require_auth();
+
+    let mut product = get_product(env.clone(), product_id);
+    if product.creator != creator {
+        panic!("Only the creator can reopen");
+    }
+    if product.status != ProductStatus::Failed {
+        panic!("Product has not failed");
+    }
+    let contributions: Vec<Contribution> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Contributions(product_id))
+        .unwrap_or_else(|| Vec::new(&env));
+    if !contributions.is_empty() {
+        panic!("Refunds have not completed");
+    }
+    // Milestone funds already paid out cannot be raised a second time
+    if product.released_funds > 0 {
+        panic!("Cannot reopen a campaign that released funds");
+    }
+    // A failed campaign that still meets its goal was Funded and failed later (missed milestone or
+    // dispute). Its milestones, disputes and backer state, including the Reclaimed flags that make
+    // refund_unreleased skip a backer, belong to that run, so only campaigns that missed their goal
+    // at the deadline start over.
+    if finalizes_as_funded(&product) {
+        panic!("Only campaigns that missed their goal can be reopened");
+    }
+    validate_deadline(&env, new_deadline);
+
+    set_status(&env, &mut product, ProductStatus::Active);
+    stats::record_unfunded(&env, product.total_funded);
+    product.total_funded = 0;
+    product.deadline = new_deadline;
+    // The new run is timed, and may auto-extend, as if it had just been created
+    product.created_at = env.ledger().timestamp();
+    product.auto_extensions = 0;
+    env.storage()
+        .persistent()
+        .set(&DataKey::Products(product_id), &product);
+    env.storage()
+        .persistent()
+        .set(&DataKey::ContributionsTotal(product_id), &0u64);
+    bump_product_ttl(&env, product_id);
+
+    env.events().publish(
+        (Symbol::new(&env, "ProductReopened"), product_id),
+        new_deadline,
+    );
+}
+
+// Explicitly extend a product's data (and the contract instance) to live for `ledgers` more ledgers
+pub fn extend_product_ttl(env: Env, product_id: u32, ledgers: u32) {
+    if !env
+        .storage()
+        .persistent()
+        .has(&DataKey::Products(product_id))
+    {
+        panic!("Product not found");
+    }
+    extend_ttl(&env, product_id, ledgers, ledgers);
+}
+
+// All status transitions go through here so the platform counters stay in sync
+pub(crate) fn set_status(env: &Env, product: &mut Product, status: ProductStatus) {
+    stats::record_status_change(env, &product.status, &status);
+    env.events().publish(
+        (
+            Symbol::new(env, "status"),
+            product.id,
+            product.creator.clone(),
+        ),
+        (product.status.clone(), status.clone()),
+    );
+    record_status_history(env, product.id, status.clone());
+    product.status = status;
+}
+
+fn record_status_history(env: &Env, product_id: u32, status: ProductStatus) {
+    let key = DataKey::StatusHistory(product_id);
+    let mut history: Vec<(ProductStatus, u64)> = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or_else(|| Vec::new(env));
+    if history.len() >= MAX_STATUS_HISTORY {
+        history.pop_front();
+    }
+    history.push_back((status, env.ledger().timestamp()));
+    env.storage().persistent().set(&key, &history);
+}
+
+// Products created without an explicit model use the admin default, AllOrNothing until set
+pub(crate) fn default_funding_model(env: &Env) -> FundingModel {
+    env.storage()
+        .instance()
+        .get(&DataKey::DefaultFundingModel)
+        .unwrap_or(FundingModel::AllOrNothing)
+}
+
+pub(crate) fn bump_product_ttl(env: &Env, product_id: u32) {
+    extend_ttl(
+        env,
+        product_id,
+        PRODUCT_TTL_THRESHOLD,
+        PRODUCT_TTL_EXTEND_TO,
+    );
+}
+
+fn extend_ttl(env: &Env, product_id: u32, threshold: u32, extend_to: u32) {
+    let keys = [
+        DataKey::Products(product_id),
+        DataKey::Contributions(product_id),
+        DataKey::ContributionsTotal(product_id),
+        DataKey::Rewards(product_id),
+        DataKey::Milestones(product_id),
+        DataKey::StatusHistory(product_id),
+        DataKey::DistributionReport(product_id),
+        DataKey::RefundCursor(product_id),
+        DataKey::Disputes(product_id),
+        DataKey::LastCreatorWithdrawal(product_id),
+    ];
+    // Per-backer entries are bumped where they are written instead, so this costs the same number
+    // of reads however many backers the product has
+    for key in keys.iter() {
+        extend_entry_ttl(env, key, threshold, extend_to);
+    }
+    env.storage().instance().extend_ttl(threshold, extend_to);
+}
+
+fn extend_entry_ttl(env: &Env, key: &DataKey, threshold: u32, extend_to: u32) {
+    if env.storage().persistent().has(key) {
+        env.storage()
+            .persistent()
+            .extend_ttl(key, threshold, extend_to);
+    }
+}
+
+fn validate_metadata_uri(metadata_uri: &String) {
+    if metadata_uri.len() > MAX_METADATA_URI_LENGTH {
+        panic!("Metadata URI too long");
+    }
+}
+
+fn validate_funding_goal(env: &Env, funding_goal: u64) {
+    if funding_goal == 0 {
+        panic!("Funding goal must be greater than zero");
+    }
+    let max_goal: u64 = env
+        .storage()
+        .instance()
+        .get(&DataKey::MaxFundingGoal)
+        .unwrap_or(0);
+    if max_goal > 0 && funding_goal > max_goal {
+        panic!("Funding goal exceeds maximum");
+    }
+}
+
+// Claims are counted by claim_reward, so a creator cannot pre-fill them to exhaust a tier's supply
+fn fresh_reward_tiers(env: &Env, reward_tiers: &Vec<RewardTier>) -> Vec<RewardTier> {
+    let mut fresh = Vec::new(env);
+    for mut tier in reward_tiers.iter() {
+        tier.claimed = 0;
+        fresh.push_back(tier);
+    }
+    fresh
+}
+
+// Milestone state is only earned after funding, so whatever the creator passed in starts cleared
+fn fresh_milestones(env: &Env, milestones: &Vec<Milestone>) -> Vec<Milestone> {
+    let mut fresh = Vec::new(env);
+    for mut milestone in milestones.iter() {
+        milestone.completed = false;
+        milestone.released = false;
+        milestone.progress = 0;
+        milestone.completion_note = String::from_str(env, "");
+        fresh.push_back(milestone);
+    }
+    fresh
+}
+
+fn validate_deadline(env: &Env, deadline: u64) {
+    let now = env.ledger().timestamp();
+    if deadline <= now {
+        panic!("Deadline must be in the future");
+    }
+    let min_duration: u64 = env
+        .storage()
+        .instance()
+        .get(&DataKey::MinCampaignDuration)
+        .unwrap_or(0);
+    if deadline - now < min_duration {
+        panic!("Campaign too short");
+    }
+}
+
 fn next_product_id(env: &Env) -> u32 {
     let product_id = env
         .storage()
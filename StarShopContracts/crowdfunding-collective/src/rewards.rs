@@ -1,66 +1,227 @@
+use crate::product::{PRODUCT_TTL_EXTEND_TO, PRODUCT_TTL_THRESHOLD};
+use crate::tracking::merged_contributions;
 use crate::types::*;
-use soroban_sdk::{Address, Env, Vec};
+use soroban_sdk::{Address, Env, Symbol, Vec};
 
 pub fn claim_reward(env: Env, contributor: Address, product_id: u32) {
     contributor.require_auth();
 
     let product: Product = env
         .storage()
-        .instance()
+        .persistent()
         .get(&DataKey::Products(product_id))
         .unwrap_or_else(|| panic!("Product not found"));
 
     if product.status != ProductStatus::Completed {
         panic!("Product is not completed");
     }
+    if product.reward_claim_deadline > 0 && env.ledger().timestamp() > product.reward_claim_deadline
+    {
+        panic!("Reward claim window closed");
+    }
 
-    // Get contributor's total contribution
-    let contributions: Vec<Contribution> = env
-        .storage()
-        .instance()
-        .get(&DataKey::Contributions(product_id))
-        .unwrap_or_else(|| Vec::new(&env));
-    let total_contributed: u64 = contributions
-        .iter()
-        .filter(|c| c.contributor == contributor)
-        .map(|c| c.amount)
-        .sum();
-
+    let total_contributed = contributed_by(&env, product_id, &contributor);
     if total_contributed == 0 {
         panic!("No contributions found for this contributor");
     }
 
-    // Find eligible reward tier
-    let reward_tiers: Vec<RewardTier> = env
+    let claimed_key = DataKey::RewardClaimed(product_id, contributor.clone());
+    if env.storage().persistent().has(&claimed_key) {
+        panic!("Reward already claimed");
+    }
+
+    let mut reward_tiers: Vec<RewardTier> = env
         .storage()
-        .instance()
+        .persistent()
         .get(&DataKey::Rewards(product_id))
         .unwrap_or_else(|| Vec::new(&env));
-    let mut eligible_tier: Option<RewardTier> = None;
-    for tier in reward_tiers.iter() {
-        if total_contributed >= tier.min_contribution {
-            if eligible_tier.is_none()
-                || tier.min_contribution > eligible_tier.as_ref().unwrap().min_contribution
-            {
-                eligible_tier = Some(tier.clone());
-            }
-        }
-    }
-
-    if eligible_tier.is_none() {
+    let (eligible_found, selected) =
+        select_tier(&reward_tiers, total_contributed, &product.reward_strategy);
+    if !eligible_found {
         panic!("No eligible reward tier found");
     }
+    let index = selected.unwrap_or_else(|| panic!("Reward tier sold out"));
+
+    let mut tier = reward_tiers.get(index).unwrap();
+    tier.claimed += 1;
+    reward_tiers.set(index, tier.clone());
+    env.storage()
+        .persistent()
+        .set(&DataKey::Rewards(product_id), &reward_tiers);
+    env.storage().persistent().set(&claimed_key, &true);
+    env.storage().persistent().extend_ttl(
+        &claimed_key,
+        PRODUCT_TTL_THRESHOLD,
+        PRODUCT_TTL_EXTEND_TO,
+    );
 
     // Emit event for reward claim (actual reward distribution is off-chain)
     env.events().publish(
-        ("RewardClaimed", product_id, contributor),
-        eligible_tier.unwrap().id,
+        (Symbol::new(&env, "reward_claimed"), product_id),
+        (contributor, tier.id, tier.discount),
     );
 }
 
+// Creators record off-chain delivery of a claimed reward
+pub fn mark_reward_fulfilled(env: Env, creator: Address, product_id: u32, contributor: Address) {
+    creator.require_auth();
+
+    let product: Product = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Products(product_id))
+        .unwrap_or_else(|| panic!("Product not found"));
+    if product.creator != creator {
+        panic!("Only the creator can fulfill rewards");
+    }
+    let claimed_key = DataKey::RewardClaimed(product_id, contributor.clone());
+    if !env.storage().persistent().has(&claimed_key) {
+        panic!("Reward has not been claimed");
+    }
+    env.storage().persistent().extend_ttl(
+        &claimed_key,
+        PRODUCT_TTL_THRESHOLD,
+        PRODUCT_TTL_EXTEND_TO,
+    );
+
+    let fulfilled_key = DataKey::RewardFulfilled(product_id, contributor.clone());
+    if env.storage().persistent().has(&fulfilled_key) {
+        panic!("Reward already fulfilled");
+    }
+    env.storage().persistent().set(&fulfilled_key, &true);
+    env.storage().persistent().extend_ttl(
+        &fulfilled_key,
+        PRODUCT_TTL_THRESHOLD,
+        PRODUCT_TTL_EXTEND_TO,
+    );
+
+    env.events().publish(
+        (Symbol::new(&env, "reward_fulfilled"), product_id),
+        contributor,
+    );
+}
+
+pub fn is_reward_fulfilled(env: Env, product_id: u32, contributor: Address) -> bool {
+    env.storage()
+        .persistent()
+        .has(&DataKey::RewardFulfilled(product_id, contributor))
+}
+
 pub fn get_reward_tiers(env: Env, product_id: u32) -> Vec<RewardTier> {
     env.storage()
-        .instance()
+        .persistent()
         .get(&DataKey::Rewards(product_id))
         .unwrap_or_else(|| Vec::new(&env))
 }
+
+// Tiers by ascending min_contribution. Creation already requires increasing thresholds, so this
+// only reorders tiers stored before that check existed.
+pub fn get_reward_tiers_sorted(env: Env, product_id: u32) -> Vec<RewardTier> {
+    let mut sorted: Vec<RewardTier> = Vec::new(&env);
+    for tier in get_reward_tiers(env.clone(), product_id).iter() {
+        let position = sorted
+            .iter()
+            .position(|other| tier.min_contribution < other.min_contribution)
+            .unwrap_or(sorted.len() as usize);
+        sorted.insert(position as u32, tier);
+    }
+    sorted
+}
+
+// (tier_id, total contributed, backer count) per tier, in tier order. Each backer counts once, in
+// the highest tier their total qualifies for regardless of supply; backers below every tier are
+// left out.
+pub fn get_tier_breakdown(env: Env, product_id: u32) -> Vec<(u32, u64, u32)> {
+    let reward_tiers = get_reward_tiers(env.clone(), product_id);
+    let mut breakdown = Vec::new(&env);
+    for tier in reward_tiers.iter() {
+        breakdown.push_back((tier.id, 0u64, 0u32));
+    }
+
+    for contribution in merged_contributions(&env, product_id).iter() {
+        let highest = reward_tiers
+            .iter()
+            .enumerate()
+            .filter(|(_, tier)| contribution.amount >= tier.min_contribution)
+            .max_by_key(|(_, tier)| tier.min_contribution);
+        if let Some((index, _)) = highest {
+            let index = index as u32;
+            let (id, total, count) = breakdown.get(index).unwrap();
+            breakdown.set(index, (id, total + contribution.amount, count + 1));
+        }
+    }
+    breakdown
+}
+
+// The tier a backer would receive if they claimed now; None when no tier with supply qualifies
+pub fn preview_reward_tier(env: Env, product_id: u32, contributor: Address) -> Option<RewardTier> {
+    let total_contributed = contributed_by(&env, product_id, &contributor);
+    if total_contributed == 0 {
+        return None;
+    }
+    let product: Product = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Products(product_id))?;
+    let reward_tiers = get_reward_tiers(env, product_id);
+    let (_, selected) = select_tier(&reward_tiers, total_contributed, &product.reward_strategy);
+    selected.map(|index| reward_tiers.get(index).unwrap())
+}
+
+// Tiers must be listed by increasing id with strictly increasing thresholds, so every backer
+// qualifies for an unambiguous set of tiers
+pub(crate) fn validate_reward_tiers(reward_tiers: &Vec<RewardTier>) {
+    for i in 1..reward_tiers.len() {
+        let previous = reward_tiers.get(i - 1).unwrap();
+        let tier = reward_tiers.get(i).unwrap();
+        if tier.id <= previous.id || tier.min_contribution <= previous.min_contribution {
+            panic!("Reward tiers must have increasing thresholds");
+        }
+    }
+}
+
+fn contributed_by(env: &Env, product_id: u32, contributor: &Address) -> u64 {
+    let contributions: Vec<Contribution> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Contributions(product_id))
+        .unwrap_or_else(|| Vec::new(env));
+    contributions
+        .iter()
+        .filter(|c| c.contributor == *contributor)
+        .map(|c| c.amount)
+        .sum()
+}
+
+// Pick the eligible tier with supply that ranks best under the product's strategy, skipping
+// sold-out tiers. Also reports whether any tier was eligible at all, so sold-out tiers can be
+// told apart.
+fn select_tier(
+    reward_tiers: &Vec<RewardTier>,
+    total_contributed: u64,
+    strategy: &RewardStrategy,
+) -> (bool, Option<u32>) {
+    let mut eligible_found = false;
+    let mut selected: Option<u32> = None;
+    for (i, tier) in reward_tiers.iter().enumerate() {
+        if total_contributed < tier.min_contribution {
+            continue;
+        }
+        eligible_found = true;
+        let sold_out = tier.max_claims > 0 && tier.claimed >= tier.max_claims;
+        let is_better = match selected {
+            Some(index) => {
+                let current = reward_tiers.get(index).unwrap().min_contribution;
+                match strategy {
+                    RewardStrategy::Highest => tier.min_contribution > current,
+                    RewardStrategy::Lowest => tier.min_contribution < current,
+                }
+            }
+            None => true,
+        };
+        if !sold_out && is_better {
+            selected = Some(i as u32);
+        }
+    }
+    (eligible_found, selected)
+}
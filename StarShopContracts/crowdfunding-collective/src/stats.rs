@@ -0,0 +1,79 @@
+use crate::types::*;
+use soroban_sdk::Env;
+
+// Platform-wide counters are kept up to date as products change, so reading them never scans
+pub fn get_platform_stats(env: Env) -> PlatformStats {
+    load(&env)
+}
+
+pub fn get_active_count(env: Env) -> u32 {
+    load(&env).active_count
+}
+
+// Products that left Active: funded, failed, completed or cancelled
+pub fn get_closed_count(env: Env) -> u32 {
+    let stats = load(&env);
+    stats.funded_count + stats.failed_count + stats.completed_count + stats.cancelled_count
+}
+
+pub(crate) fn record_product_created(env: &Env) {
+    let mut stats = load(env);
+    stats.total_products += 1;
+    stats.active_count += 1;
+    save(env, &stats);
+}
+
+pub(crate) fn record_status_change(env: &Env, from: &ProductStatus, to: &ProductStatus) {
+    if from == to {
+        return;
+    }
+    let mut stats = load(env);
+    *count_for(&mut stats, from) -= 1;
+    *count_for(&mut stats, to) += 1;
+    save(env, &stats);
+}
+
+pub(crate) fn record_funded(env: &Env, amount: u64) {
+    let mut stats = load(env);
+    stats.total_funded_all = stats
+        .total_funded_all
+        .checked_add(amount)
+        .unwrap_or_else(|| panic!("Funding overflow"));
+    save(env, &stats);
+}
+
+pub(crate) fn record_unfunded(env: &Env, amount: u64) {
+    let mut stats = load(env);
+    stats.total_funded_all -= amount;
+    save(env, &stats);
+}
+
+fn count_for<'a>(stats: &'a mut PlatformStats, status: &ProductStatus) -> &'a mut u32 {
+    match status {
+        ProductStatus::Active => &mut stats.active_count,
+        ProductStatus::Funded => &mut stats.funded_count,
+        // A failed milestone fails the campaign too
+        ProductStatus::Failed | ProductStatus::MilestoneFailed => &mut stats.failed_count,
+        ProductStatus::Completed => &mut stats.completed_count,
+        ProductStatus::Cancelled => &mut stats.cancelled_count,
+    }
+}
+
+fn load(env: &Env) -> PlatformStats {
+    env.storage()
+        .instance()
+        .get(&DataKey::PlatformStats)
+        .unwrap_or(PlatformStats {
+            total_products: 0,
+            total_funded_all: 0,
+            active_count: 0,
+            funded_count: 0,
+            failed_count: 0,
+            completed_count: 0,
+            cancelled_count: 0,
+        })
+}
+
+fn save(env: &Env, stats: &PlatformStats) {
+    env.storage().instance().set(&DataKey::PlatformStats, stats);
+}
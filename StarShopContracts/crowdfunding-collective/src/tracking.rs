@@ -1,53 +1,383 @@
+use crate::funding::milestone_allocation;
+use crate::product::{bump_product_ttl, get_product, get_product_or_none, MAX_PAGE_SIZE};
 use crate::types::*;
-use soroban_sdk::{Address, Env, Vec};
+use soroban_sdk::{Address, Env, String, Vec};
 
-pub fn update_milestone(env: Env, creator: Address, product_id: u32, milestone_id: u32) {
+// Milestone progress is a percentage; reaching it marks the milestone completed
+pub const MILESTONE_COMPLETE: u32 = 100;
+
+// Upper bound on entries returned by get_top_contributors
+pub const MAX_TOP_CONTRIBUTORS: u32 = 25;
+
+// Longest completion note a creator can attach to a milestone, in bytes
+pub const MAX_COMPLETION_NOTE_LENGTH: u32 = 500;
+
+// Completes a milestone with a note pointing backers at the proof of delivery
+pub fn update_milestone(
+    env: Env,
+    creator: Address,
+    product_id: u32,
+    milestone_id: u32,
+    completion_note: String,
+) {
+    if completion_note.len() > MAX_COMPLETION_NOTE_LENGTH {
+        panic!("Note too long");
+    }
+    set_progress(
+        &env,
+        &creator,
+        product_id,
+        milestone_id,
+        MILESTONE_COMPLETE,
+        Some(completion_note),
+    );
+}
+
+// Completing a milestone is the same as reporting 100% progress on it
+pub fn set_milestone_progress(
+    env: Env,
+    creator: Address,
+    product_id: u32,
+    milestone_id: u32,
+    progress: u32,
+) {
+    set_progress(&env, &creator, product_id, milestone_id, progress, None);
+}
+
+fn set_progress(
+    env: &Env,
+    creator: &Address,
+    product_id: u32,
+    milestone_id: u32,
+    progress: u32,
+    completion_note: Option<String>,
+) {
     creator.require_auth();
 
     let product: Product = env
         .storage()
-        .instance()
+        .persistent()
         .get(&DataKey::Products(product_id))
         .unwrap_or_else(|| panic!("Product not found"));
 
-    if product.creator != creator {
+    if product.creator != *creator {
         panic!("Only the creator can update milestones");
     }
     if product.status != ProductStatus::Funded {
         panic!("Product is not funded");
     }
+    if progress > MILESTONE_COMPLETE {
+        panic!("Progress cannot exceed 100");
+    }
 
     let mut milestones: Vec<Milestone> = env
         .storage()
-        .instance()
+        .persistent()
         .get(&DataKey::Milestones(product_id))
-        .unwrap_or_else(|| Vec::new(&env));
+        .unwrap_or_else(|| Vec::new(env));
 
     let mut milestone = milestones.get(milestone_id).unwrap();
     if milestone.completed {
         panic!("Milestone already completed");
     }
+    if progress < milestone.progress {
+        panic!("Progress cannot decrease");
+    }
 
-    milestone.completed = true;
+    let completed = progress == MILESTONE_COMPLETE;
+    if completed
+        && product.enforce_milestone_dates
+        && env.ledger().timestamp() < milestone.target_date
+    {
+        panic!("Milestone target date not reached");
+    }
+
+    milestone.progress = progress;
+    milestone.completed = completed;
+    if let Some(note) = completion_note {
+        milestone.completion_note = note;
+    }
     milestones.set(milestone_id, milestone);
     env.storage()
-        .instance()
+        .persistent()
+        .set(&DataKey::Milestones(product_id), &milestones);
+    bump_product_ttl(env, product_id);
+
+    if completed {
+        env.events()
+            .publish(("MilestoneCompleted", product_id), milestone_id);
+    } else {
+        env.events()
+            .publish(("MilestoneProgress", product_id, milestone_id), progress);
+    }
+}
+
+// Milestones are addressed by index, so the ones after the removed entry are renumbered
+pub fn remove_milestone(env: Env, creator: Address, product_id: u32, milestone_id: u32) {
+    creator.require_auth();
+
+    let product = get_product(env.clone(), product_id);
+    if product.creator != creator {
+        panic!("Only the creator can update milestones");
+    }
+    if product.status != ProductStatus::Active {
+        panic!("Product is not active");
+    }
+
+    let mut milestones = get_milestones(env.clone(), product_id);
+    if milestone_id >= milestones.len() {
+        panic!("Milestone not found");
+    }
+    milestones.remove(milestone_id);
+    validate_milestone_weights(&milestones);
+    for i in milestone_id..milestones.len() {
+        let mut milestone = milestones.get(i).unwrap();
+        milestone.id = i;
+        milestones.set(i, milestone);
+    }
+    env.storage()
+        .persistent()
         .set(&DataKey::Milestones(product_id), &milestones);
 
     env.events()
-        .publish(("MilestoneCompleted", product_id), milestone_id);
+        .publish(("MilestoneRemoved", product_id), milestone_id);
 }
 
-pub fn get_contributions(env: Env, product_id: u32) -> Vec<Contribution> {
+// `new_order[i]` is the current index of the milestone that moves to position i; it must list
+// every current index exactly once. IDs are reassigned to match the new positions.
+pub fn reorder_milestones(env: Env, creator: Address, product_id: u32, new_order: Vec<u32>) {
+    creator.require_auth();
+
+    let product = get_product(env.clone(), product_id);
+    if product.creator != creator {
+        panic!("Only the creator can update milestones");
+    }
+    if product.status != ProductStatus::Active {
+        panic!("Product is not active");
+    }
+
+    let milestones = get_milestones(env.clone(), product_id);
+    if new_order.len() != milestones.len() {
+        panic!("Invalid milestone order");
+    }
+    let mut reordered: Vec<Milestone> = Vec::new(&env);
+    for (position, index) in new_order.iter().enumerate() {
+        if index >= milestones.len() || new_order.first_index_of(index) != Some(position as u32) {
+            panic!("Invalid milestone order");
+        }
+        let mut milestone = milestones.get(index).unwrap();
+        milestone.id = position as u32;
+        reordered.push_back(milestone);
+    }
     env.storage()
-        .instance()
-        .get(&DataKey::Contributions(product_id))
-        .unwrap_or_else(|| Vec::new(&env))
+        .persistent()
+        .set(&DataKey::Milestones(product_id), &reordered);
+
+    env.events()
+        .publish(("MilestonesReordered", product_id), new_order);
+}
+
+pub fn get_contributions(env: Env, product_id: u32) -> Vec<Contribution> {
+    anonymized(&env, product_id, stored_contributions(&env, product_id))
+}
+
+// Distinct backer addresses in the order of their first contribution
+pub fn get_all_contributors(env: Env, product_id: u32) -> Vec<Address> {
+    let merged = merged_contributions(&env, product_id);
+    let mut contributors = Vec::new(&env);
+    for contribution in anonymized(&env, product_id, merged).iter() {
+        contributors.push_back(contribution.contributor);
+    }
+    contributors
+}
+
+// The contributor's merged entry: summed amount and latest contribution time, masked like the
+// listing views on anonymous products
+pub fn get_contribution(env: Env, product_id: u32, contributor: Address) -> Option<Contribution> {
+    let entry = merged_contributions(&env, product_id)
+        .iter()
+        .find(|entry| entry.contributor == contributor)?;
+    anonymized(&env, product_id, Vec::from_array(&env, [entry])).get(0)
+}
+
+// Unknown products have no contributions, so they report false
+pub fn is_contributor(env: Env, product_id: u32, addr: Address) -> bool {
+    stored_contributions(&env, product_id)
+        .iter()
+        .any(|contribution| contribution.contributor == addr)
+}
+
+// Window of `limit` contributions (capped at MAX_PAGE_SIZE) starting at index `start`
+pub fn get_contributions_paged(
+    env: Env,
+    product_id: u32,
+    start: u32,
+    limit: u32,
+) -> Vec<Contribution> {
+    let contributions = get_contributions(env.clone(), product_id);
+    let end = start
+        .saturating_add(limit.min(MAX_PAGE_SIZE))
+        .min(contributions.len());
+    if start >= end {
+        return Vec::new(&env);
+    }
+    contributions.slice(start..end)
+}
+
+// Highest backers first, by their merged per-address total; ties go to the lower address
+pub fn get_top_contributors(env: Env, product_id: u32, n: u32) -> Vec<Contribution> {
+    let n = n.min(MAX_TOP_CONTRIBUTORS);
+    let mut ranked: Vec<Contribution> = Vec::new(&env);
+    for contribution in merged_contributions(&env, product_id).iter() {
+        let mut position = ranked.len();
+        for (i, other) in ranked.iter().enumerate() {
+            if contribution.amount > other.amount
+                || (contribution.amount == other.amount
+                    && contribution.contributor < other.contributor)
+            {
+                position = i as u32;
+                break;
+            }
+        }
+        ranked.insert(position, contribution);
+    }
+
+    while ranked.len() > n {
+        ranked.pop_back();
+    }
+    anonymized(&env, product_id, ranked)
+}
+
+// Merged per-backer entries whose total lies within [min, max], in first-contribution order
+pub fn get_contributions_by_amount(
+    env: Env,
+    product_id: u32,
+    min: u64,
+    max: u64,
+) -> Vec<Contribution> {
+    let mut in_range = Vec::new(&env);
+    for contribution in merged_contributions(&env, product_id).iter() {
+        if contribution.amount >= min && contribution.amount <= max {
+            in_range.push_back(contribution);
+        }
+    }
+    anonymized(&env, product_id, in_range)
+}
+
+pub fn get_product_summary(env: Env, product_id: u32) -> ProductSummary {
+    let product = get_product(env.clone(), product_id);
+    ProductSummary {
+        id: product.id,
+        status: product.status,
+        total_funded: product.total_funded,
+        funding_goal: product.funding_goal,
+        deadline: product.deadline,
+        backer_count: merged_contributions(&env, product_id).len(),
+        milestone_count: get_milestones(env, product_id).len(),
+    }
+}
+
+// Mean amount per distinct backer, rounded down; 0 without backers
+pub fn get_average_contribution(env: Env, product_id: u32) -> u64 {
+    let product = get_product(env.clone(), product_id);
+    let backers = merged_contributions(&env, product_id).len();
+    if backers == 0 {
+        return 0;
+    }
+    product.total_funded / backers as u64
 }
 
 pub fn get_milestones(env: Env, product_id: u32) -> Vec<Milestone> {
     env.storage()
-        .instance()
+        .persistent()
         .get(&DataKey::Milestones(product_id))
         .unwrap_or_else(|| Vec::new(&env))
 }
+
+// Unknown products have no milestones, so they report 0
+pub fn get_completed_milestone_count(env: Env, product_id: u32) -> u32 {
+    get_milestones(env, product_id)
+        .iter()
+        .filter(|milestone| milestone.completed)
+        .count() as u32
+}
+
+// The share of total_funded that release_milestone_funds pays for this milestone
+pub fn get_milestone_allocation(env: Env, product_id: u32, milestone_id: u32) -> u64 {
+    let product = get_product(env.clone(), product_id);
+    let milestones = get_milestones(env, product_id);
+    if milestone_id >= milestones.len() {
+        panic!("Milestone not found");
+    }
+    milestone_allocation(product.total_funded, &milestones, milestone_id)
+}
+
+// Seconds until the milestone's target date, 0 once it has passed
+pub fn get_milestone_time_remaining(env: Env, product_id: u32, milestone_id: u32) -> u64 {
+    let milestone = get_milestones(env.clone(), product_id)
+        .get(milestone_id)
+        .unwrap_or_else(|| panic!("Milestone not found"));
+    milestone
+        .target_date
+        .saturating_sub(env.ledger().timestamp())
+}
+
+// Funds are split by weight, so a product with milestones needs at least one weighted milestone
+pub(crate) fn validate_milestone_weights(milestones: &Vec<Milestone>) {
+    if !milestones.is_empty() && milestones.iter().all(|milestone| milestone.weight == 0) {
+        panic!("Milestone weights must not all be zero");
+    }
+}
+
+// One entry per contributor with their summed amount and latest contribution time
+pub(crate) fn merged_contributions(env: &Env, product_id: u32) -> Vec<Contribution> {
+    let contributions = stored_contributions(env, product_id);
+    let mut merged: Vec<Contribution> = Vec::new(env);
+    for contribution in contributions.iter() {
+        match merged
+            .iter()
+            .position(|entry| entry.contributor == contribution.contributor)
+        {
+            Some(i) => {
+                let mut entry = merged.get(i as u32).unwrap();
+                entry.amount += contribution.amount;
+                // A backer who paid in several tokens has no single token amount, so the merged
+                // entry reports 0 instead of a partial sum; the stored entries keep each token's
+                if entry.token == contribution.token && entry.token_amount > 0 {
+                    entry.token_amount += contribution.token_amount;
+                } else {
+                    entry.token_amount = 0;
+                }
+                entry.timestamp = contribution.timestamp;
+                merged.set(i as u32, entry);
+            }
+            None => merged.push_back(contribution),
+        }
+    }
+    merged
+}
+
+fn stored_contributions(env: &Env, product_id: u32) -> Vec<Contribution> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Contributions(product_id))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+// Anonymous products list every backer as the contract's own address and hide refund addresses.
+// Only these views change; refunds and payouts read the stored contributions.
+fn anonymized(env: &Env, product_id: u32, contributions: Vec<Contribution>) -> Vec<Contribution> {
+    let anonymous = get_product_or_none(env.clone(), product_id)
+        .map(|product| product.anonymous)
+        .unwrap_or(false);
+    if !anonymous {
+        return contributions;
+    }
+    let mut hidden = Vec::new(env);
+    for mut contribution in contributions.iter() {
+        contribution.contributor = env.current_contract_address();
+        contribution.refund_to = None;
+        hidden.push_back(contribution);
+    }
+    hidden
+}
@@ -1,14 +1,42 @@
-use soroban_sdk::{contracttype, Address, String};
+use soroban_sdk::{contracttype, Address, String, Symbol, Vec};
 
 #[contracttype]
 pub enum DataKey {
-    Admin,                   // Admin address
-    Products(u32),           // Product ID -> Product
-    Contributions(u32),      // Product ID -> Vec<Contribution>
-    Rewards(u32),            // Product ID -> Vec<RewardTier>
-    Milestones(u32),         // Product ID -> Vec<Milestone>
-    NextProductId,           // Counter for product IDs
-    ContributionsTotal(u32), // Product ID -> Total contributed amount
+    Admins,                         // Vec<Address> of admins, the first receives fees
+    Products(u32),                  // Product ID -> Product
+    Contributions(u32),             // Product ID -> Vec<Contribution>
+    Rewards(u32),                   // Product ID -> Vec<RewardTier>
+    Milestones(u32),                // Product ID -> Vec<Milestone>
+    NextProductId,                  // Counter for product IDs
+    ContributionsTotal(u32),        // Product ID -> Total contributed amount
+    CategoryProducts(Symbol),       // Category -> Vec<u32> of product IDs
+    Token,                          // Token used by newly created products
+    NativeToken,                    // SAC address of the native asset (XLM)
+    WithdrawFeeBps,                 // Fee on voluntary withdrawals, in basis points
+    RewardClaimed(u32, Address),    // (Product ID, contributor) -> whether the reward was claimed
+    RewardFulfilled(u32, Address),  // (Product ID, contributor) -> whether the creator delivered it
+    MinCampaignDuration,            // Shortest allowed campaign, in seconds
+    MaxFundingGoal,                 // Largest allowed funding goal, 0 for unlimited
+    MaxMilestones,                  // Most milestones a product may have
+    MaxRewardTiers,                 // Most reward tiers a product may have
+    FailureBonusBps,                // Bonus on failed-campaign refunds, in basis points
+    BonusPool(Address),             // Token -> balance set aside for failure bonuses
+    RefundCursor(u32),              // Product ID -> index of the next contribution to refund
+    PlatformStats,                  // Counters across all products
+    LastContribution(u32, Address), // (Product ID, contributor) -> last contribution time
+    Disputes(u32),                  // Product ID -> Vec<Dispute>
+    ContributorProducts(Address),   // Contributor -> Vec<u32> of product IDs they backed
+    ReentrancyLock,                 // Held while a token-moving call is running
+    RefundGracePeriod,              // Seconds after the deadline before refunds open
+    VerifiedCreator(Address),       // Creator -> present once an admin verified them
+    RequireVerifiedCreators,        // Whether only verified creators may create products
+    StatusHistory(u32),             // Product ID -> Vec<(ProductStatus, u64)> of transitions
+    LastCreatorWithdrawal(u32),     // Product ID -> time of the creator's latest withdrawal
+    Reclaimed(u32, Address),        // (Product ID, contributor) -> whether they reclaimed
+    DefaultFundingModel,            // FundingModel used when create_product is not given one
+    Escrowed(Address),              // Token -> campaign funds held and not yet paid out
+    CreatorProducts(Address),       // Creator -> Vec<u32> of product IDs they created
+    DistributionReport(u32),        // Product ID -> DistributionReport from distribute_funds
 }
 
 #[contracttype]
@@ -18,10 +46,93 @@ pub struct Product {
     pub creator: Address,
     pub name: String,
     pub description: String,
-    pub funding_goal: u64, // In XLM (stroops)
-    pub deadline: u64,     // Ledger timestamp
+    pub metadata_uri: String, // Off-chain JSON or cover image, empty until the creator sets one
+    pub funding_goal: u64,    // In XLM (stroops)
+    pub deadline: u64,        // Ledger timestamp
     pub status: ProductStatus,
-    pub total_funded: u64, // Total funds collected
+    pub total_funded: u64,                    // Total funds collected
+    pub enforce_milestone_dates: bool,        // Block milestone completion before its target date
+    pub released_funds: u64,                  // Funds already released through milestones
+    pub category: Symbol,                     // Theme used for browsing, e.g. "tech" or "art"
+    pub token: Address,                       // Token contributions are made in
+    pub decimals: u32,                        // Decimals of `token`, read at creation
+    pub contribution_cooldown: u64, // Seconds between a backer's contributions, 0 to disable
+    pub dispute_count: u32,         // Disputes ever raised by backers
+    pub disputed_amount: u64,       // Contribution weight behind currently open disputes
+    pub reward_strategy: RewardStrategy, // Which tier a backer gets when several match
+    pub funding_model: FundingModel, // How the funding goal caps and closes the campaign
+    pub beneficiary: Address,       // Receives released funds, the creator by default
+    pub created_at: u64,            // Ledger timestamp of creation or latest reopen
+    pub accept_partial: bool,       // Trim contributions that would overshoot the goal
+    pub accepted_tokens: Vec<(Address, u64)>, // (token, weight per mille) contributions can use
+    pub anonymous: bool,            // Hide backer addresses from contribution listings
+    pub reward_claim_period: u64, // Seconds after completion rewards stay claimable, 0 for no limit
+    pub reward_claim_deadline: u64, // Set from reward_claim_period on completion, 0 for no limit
+    pub auto_extend: bool,        // Extend instead of failing when close to the goal at expiry
+    pub auto_extend_window: u64,  // Seconds after the deadline an extension can still happen
+    pub auto_extend_by: u64,      // Seconds the campaign reopens for on each extension
+    pub auto_extensions: u32,     // Extensions used, capped at MAX_AUTO_EXTENSIONS
+    pub max_contributors: u32,    // Distinct backers allowed, 0 for no limit
+    pub allow_self_contribution: bool, // Whether the creator may back their own campaign
+    pub paused: bool,             // Creator halted contributions for now
+}
+
+// Compact view of a product for list screens
+#[contracttype]
+#[derive(Clone)]
+pub struct ProductSummary {
+    pub id: u32,
+    pub status: ProductStatus,
+    pub total_funded: u64,
+    pub funding_goal: u64,
+    pub deadline: u64,
+    pub backer_count: u32, // Distinct contributor addresses
+    pub milestone_count: u32,
+}
+
+// Close-out figures recorded when distribute_funds completes a product
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct DistributionReport {
+    pub product_id: u32,
+    pub total_raised: u64,
+    pub released_before: u64, // Already paid out through milestone releases
+    pub fee: u64,             // Withheld from the payout; distributions charge none today
+    pub distributed: u64,     // Paid to the beneficiary by this distribution
+    pub beneficiary: Address,
+    pub backer_count: u32, // Distinct contributor addresses
+    pub milestones_completed: u32,
+    pub timestamp: u64,
+}
+
+// Every admin setting in one read, with the defaults that apply until an admin sets them
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContractConfig {
+    pub token: Option<Address>, // Token for create_product, None until set_token
+    pub native_token: Option<Address>, // Token for create_native_product, None until set_native_token
+    pub withdraw_fee_bps: u32,
+    pub min_campaign_duration: u64, // Seconds
+    pub max_funding_goal: u64,      // 0 for unlimited
+    pub max_milestones: u32,
+    pub max_reward_tiers: u32,
+    pub refund_grace_period: u64, // Seconds
+    pub failure_bonus_bps: u32,
+    pub require_verified_creators: bool,
+    pub default_funding_model: FundingModel,
+}
+
+// Platform-wide metrics, maintained incrementally
+#[contracttype]
+#[derive(Clone)]
+pub struct PlatformStats {
+    pub total_products: u32,
+    pub total_funded_all: u64, // Sum of every product's total_funded
+    pub active_count: u32,
+    pub funded_count: u32,
+    pub failed_count: u32,
+    pub completed_count: u32,
+    pub cancelled_count: u32,
 }
 
 #[contracttype]
@@ -31,13 +142,47 @@ pub enum ProductStatus {
     Funded,
     Failed,
     Completed,
+    MilestoneFailed, // A milestone missed its target date and the unreleased funds were refunded
+    Cancelled,       // The creator called the campaign off and the unreleased funds were refunded
+}
+
+// How contributions relate to the funding goal
+#[contracttype]
+#[derive(Clone, PartialEq, Debug)]
+pub enum FundingModel {
+    AllOrNothing, // Capped at the goal, reaching it funds the campaign immediately (default)
+    Overfunding,  // May exceed the goal, funded at the deadline or by close_funding
+    Flexible,     // Like Overfunding, but the creator keeps whatever was raised even below goal
+}
+
+// How claim_reward chooses among the tiers a backer qualifies for
+#[contracttype]
+#[derive(Clone, PartialEq, Debug)]
+pub enum RewardStrategy {
+    Highest, // The tier with the largest min_contribution (default)
+    Lowest,  // The tier with the smallest min_contribution
 }
 
 #[contracttype]
 #[derive(Clone)]
 pub struct Contribution {
     pub contributor: Address,
-    pub amount: u64, // In XLM (stroops)
+    pub amount: u64, // Credited toward the goal, after the token's weight
+    pub timestamp: u64,
+    pub refund_to: Option<Address>, // Where refunds go instead of the contributor, if set
+    pub message: Option<String>,    // Public note from the backer
+    pub token: Address,             // Token the contribution was paid in
+    // Amount paid in that token, returned on refunds. Merged views report 0 for a backer who paid
+    // in several tokens.
+    pub token_amount: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Dispute {
+    pub contributor: Address,
+    pub reason: String,
+    pub weight: u64, // The contributor's total contribution when the dispute was raised
     pub timestamp: u64,
 }
 
@@ -48,6 +193,8 @@ pub struct RewardTier {
     pub min_contribution: u64, // Minimum contribution for this tier
     pub description: String,   // E.g., "Discounted product" or "Exclusive perk"
     pub discount: u32,         // Percentage discount (0-100)
+    pub max_claims: u32,       // Supply of this reward, 0 for unlimited
+    pub claimed: u32,          // Number of times this reward has been claimed
 }
 
 #[contracttype]
@@ -57,4 +204,8 @@ pub struct Milestone {
     pub description: String,
     pub target_date: u64, // Expected completion timestamp
     pub completed: bool,
+    pub released: bool, // Whether this milestone's share of funds was released
+    pub progress: u32,  // Percentage of work done (0-100), 100 once completed
+    pub completion_note: String, // Proof or notes the creator attached on completion
+    pub weight: u32,    // Relative share of total_funded paid out for this milestone
 }